@@ -6,7 +6,7 @@ use ark_poly::{DenseMultilinearExtension, MultilinearExtension, SparseMultilinea
 use ark_std::ops::Range;
 use criterion::{black_box, BenchmarkId, Criterion};
 use gkrfold::{
-    gkr_round_sumcheck::GKRRoundSumcheck,
+    gkr_round_sumcheck::{data_structures::GateList, GKRRoundSumcheck},
     rng::{Blake2b512Rng, FeedableRNG},
 };
 
@@ -32,6 +32,22 @@ fn prove_bench<F: Field>(c: &mut Criterion) {
                 )
             });
         });
+        group.bench_with_input(BenchmarkId::new("GKR (gate list)", nv), &nv, |b, &nv| {
+            let f1 = SparseMultilinearExtension::rand_with_config(3 * nv, 1 << nv, &mut rng);
+            let gates = GateList::from_sparse_mle(&f1, nv, nv, nv);
+            let f2 = DenseMultilinearExtension::rand(nv, &mut rng);
+            let f3 = DenseMultilinearExtension::rand(nv, &mut rng);
+            let g: Vec<_> = (0..nv).map(|_| F::rand(&mut rng)).collect();
+            b.iter(|| {
+                GKRRoundSumcheck::prove_with_gates(
+                    &mut rng,
+                    black_box(&gates),
+                    black_box(&f2),
+                    black_box(&f3),
+                    black_box(&g),
+                )
+            });
+        });
     }
 }
 
@@ -46,8 +62,10 @@ fn verify_bench<F: Field>(c: &mut Criterion) {
             let f3 = DenseMultilinearExtension::rand(nv, &mut rng);
             let g: Vec<_> = (0..nv).map(|_| F::rand(&mut rng)).collect();
             let proof = GKRRoundSumcheck::prove(&mut rng, &f1, &f2, &f3, &g);
-            let expected_sum = proof.extract_sum();
-            b.iter(|| GKRRoundSumcheck::verify(&mut rng, f2.num_vars, &proof, expected_sum));
+            let expected_sum = proof.extract_sum().unwrap();
+            b.iter(|| {
+                GKRRoundSumcheck::verify(&mut rng, f2.num_vars, f3.num_vars, &proof, expected_sum)
+            });
         });
     }
 }
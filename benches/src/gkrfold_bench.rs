@@ -103,10 +103,16 @@ fn verify_bench<F: Field>(c: &mut Criterion) {
             let f3 = DenseMultilinearExtension::rand(L, &mut rng);
             let g: Vec<_> = (0..L).map(|_| F::rand(&mut rng)).collect();
             let proof = GKRRoundSumcheck::prove(&mut rng, &f1, &f2, &f3, &g);
-            let expected_sum = proof.extract_sum();
+            let expected_sum = proof.extract_sum().unwrap();
             b.iter(|| {
                 for _ in 0..n {
-                    GKRRoundSumcheck::verify(&mut rng, f2.num_vars, &proof, expected_sum);
+                    GKRRoundSumcheck::verify(
+                        &mut rng,
+                        f2.num_vars,
+                        f3.num_vars,
+                        &proof,
+                        expected_sum,
+                    );
                 }
             });
         });
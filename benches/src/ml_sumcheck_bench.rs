@@ -4,7 +4,7 @@ extern crate criterion;
 use ark_ff::Field;
 use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
 use ark_std::ops::Range;
-use ark_std::rc::Rc;
+use ark_std::sync::Arc;
 use ark_std::test_rng;
 use criterion::{black_box, BenchmarkId, Criterion};
 use gkrfold::ml_sumcheck::{protocol::ListOfProductsOfPolynomials, MLSumcheck};
@@ -18,10 +18,10 @@ fn prove_bench<F: Field>(c: &mut Criterion) {
     for nv in NUM_VARIABLES_RANGE {
         group.bench_with_input(BenchmarkId::new("SumCheck", nv), &nv, |b, &nv| {
             let product_1: Vec<_> = (0..3)
-                .map(|_| Rc::new(DenseMultilinearExtension::<F>::rand(nv, &mut rng)))
+                .map(|_| Arc::new(DenseMultilinearExtension::<F>::rand(nv, &mut rng)))
                 .collect();
             let product_2: Vec<_> = (0..3)
-                .map(|_| Rc::new(DenseMultilinearExtension::<F>::rand(nv, &mut rng)))
+                .map(|_| Arc::new(DenseMultilinearExtension::<F>::rand(nv, &mut rng)))
                 .collect();
             let coefficient_1 = F::rand(&mut rng);
             let coefficient_2 = F::rand(&mut rng);
@@ -40,10 +40,10 @@ fn verify_bench<F: Field>(c: &mut Criterion) {
     for nv in NUM_VARIABLES_RANGE {
         group.bench_with_input(BenchmarkId::new("SumCheck", nv), &nv, |b, &nv| {
             let product_1: Vec<_> = (0..3)
-                .map(|_| Rc::new(DenseMultilinearExtension::<F>::rand(nv, &mut rng)))
+                .map(|_| Arc::new(DenseMultilinearExtension::<F>::rand(nv, &mut rng)))
                 .collect();
             let product_2: Vec<_> = (0..3)
-                .map(|_| Rc::new(DenseMultilinearExtension::<F>::rand(nv, &mut rng)))
+                .map(|_| Arc::new(DenseMultilinearExtension::<F>::rand(nv, &mut rng)))
                 .collect();
             let coefficient_1 = F::rand(&mut rng);
             let coefficient_2 = F::rand(&mut rng);
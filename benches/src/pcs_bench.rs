@@ -0,0 +1,77 @@
+#[macro_use]
+extern crate criterion;
+
+use ark_ff::FftField;
+use ark_poly::{DenseMultilinearExtension, MultilinearExtension, Polynomial};
+use ark_serialize::CanonicalSerialize;
+use ark_std::ops::Range;
+use criterion::{black_box, BenchmarkId, Criterion};
+use gkrfold::{
+    pcs::{ligero::Ligero, MultilinearPCS},
+    rng::{Blake2b512Rng, FeedableRNG},
+};
+
+const NUM_VARIABLES_RANGE: Range<usize> = 16..23;
+
+fn commit_bench<F: FftField>(c: &mut Criterion) {
+    let mut rng = Blake2b512Rng::setup();
+
+    let mut group = c.benchmark_group("Commit");
+    group.sample_size(10);
+    for nv in NUM_VARIABLES_RANGE {
+        group.bench_with_input(BenchmarkId::new("Ligero", nv), &nv, |b, &nv| {
+            let pp = Ligero::<F>::setup(nv, &mut rng).unwrap();
+            let poly = DenseMultilinearExtension::<F>::rand(nv, &mut rng);
+            b.iter(|| Ligero::commit(&pp, black_box(&poly)).unwrap());
+        });
+    }
+}
+
+fn open_bench<F: FftField>(c: &mut Criterion) {
+    let mut rng = Blake2b512Rng::setup();
+
+    let mut group = c.benchmark_group("Open");
+    group.sample_size(10);
+    for nv in NUM_VARIABLES_RANGE {
+        group.bench_with_input(BenchmarkId::new("Ligero", nv), &nv, |b, &nv| {
+            let pp = Ligero::<F>::setup(nv, &mut rng).unwrap();
+            let poly = DenseMultilinearExtension::<F>::rand(nv, &mut rng);
+            let point: Vec<_> = (0..nv).map(|_| F::rand(&mut rng)).collect();
+            b.iter(|| Ligero::open(&pp, black_box(&poly), black_box(&point)).unwrap());
+        });
+    }
+}
+
+fn verify_bench<F: FftField>(c: &mut Criterion) {
+    let mut rng = Blake2b512Rng::setup();
+
+    let mut group = c.benchmark_group("Verify");
+    for nv in NUM_VARIABLES_RANGE {
+        group.bench_with_input(BenchmarkId::new("Ligero", nv), &nv, |b, &nv| {
+            let pp = Ligero::<F>::setup(nv, &mut rng).unwrap();
+            let poly = DenseMultilinearExtension::<F>::rand(nv, &mut rng);
+            let point: Vec<_> = (0..nv).map(|_| F::rand(&mut rng)).collect();
+            let value = poly.evaluate(&point);
+            let commitment = Ligero::commit(&pp, &poly).unwrap();
+            let proof = Ligero::open(&pp, &poly, &point).unwrap();
+            println!(
+                "Ligero/{}: commitment {} bytes, opening {} bytes",
+                nv,
+                commitment.compressed_size(),
+                proof.compressed_size()
+            );
+            b.iter(|| {
+                assert!(Ligero::verify(&pp, &commitment, &point, black_box(value), &proof).unwrap())
+            });
+        });
+    }
+}
+
+fn bench_bls_381(c: &mut Criterion) {
+    commit_bench::<ark_test_curves::bls12_381::Fr>(c);
+    open_bench::<ark_test_curves::bls12_381::Fr>(c);
+    verify_bench::<ark_test_curves::bls12_381::Fr>(c);
+}
+
+criterion_group!(benches, bench_bls_381);
+criterion_main!(benches);
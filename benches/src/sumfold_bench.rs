@@ -13,7 +13,7 @@ use gkrfold::{
         SumFoldInstance, SumFoldProof,
     },
 };
-use std::{rc::Rc, sync::Arc};
+use std::sync::Arc;
 
 const NUM_INSTANCE_RANGE: Range<usize> = 1..9;
 const L: usize = 16;
@@ -42,7 +42,7 @@ fn prove_bench<F: Field>(c: &mut Criterion) {
 
         group.bench_with_input(BenchmarkId::new("SumCheck", n), &n, |b, &n| {
             let product: Vec<_> = (0..3)
-                .map(|_| Rc::new(DenseMultilinearExtension::<F>::rand(L, &mut rng)))
+                .map(|_| Arc::new(DenseMultilinearExtension::<F>::rand(L, &mut rng)))
                 .collect();
             let coefficient = F::rand(&mut rng);
             let mut products = ListOfProductsOfPolynomials::new(L);
@@ -86,7 +86,7 @@ fn verify_bench<F: Field>(c: &mut Criterion) {
 
         group.bench_with_input(BenchmarkId::new("SumCheck", n), &n, |b, &n| {
             let product: Vec<_> = (0..3)
-                .map(|_| Rc::new(DenseMultilinearExtension::<F>::rand(L, &mut rng)))
+                .map(|_| Arc::new(DenseMultilinearExtension::<F>::rand(L, &mut rng)))
                 .collect();
             let coefficient = F::rand(&mut rng);
             let mut products = ListOfProductsOfPolynomials::new(L);
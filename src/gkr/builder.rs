@@ -0,0 +1,245 @@
+//! Builder of layered circuits from arbitrary arithmetic circuits made of add and mult gates.
+//!
+//! Gates may read wires of any earlier depth. When compiling, the builder assigns each gate to
+//! the layer of its depth, and relays the values that skip layers with `mult(value, 1)` gates. The
+//! constant one is itself relayed as `mult(1, 1)`. Constants are fixed values of the input layer,
+//! placed after the inputs of the circuit.
+
+use crate::gkr::data_structures::{Gate, GateType, Layer, LayeredCircuit};
+use ark_ff::Field;
+use ark_poly::DenseMultilinearExtension;
+use ark_std::collections::BTreeMap;
+use ark_std::vec::Vec;
+
+/// Handle to a wire of a circuit under construction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Wire(usize);
+
+#[derive(Clone, Copy, Debug)]
+enum Node<F: Field> {
+    Input(usize),
+    Constant(F),
+    Gate(GateType, Wire, Wire),
+}
+
+/// Builder of an arithmetic circuit with inputs, constants, add and mult gates.
+pub struct CircuitBuilder<F: Field> {
+    nodes: Vec<Node<F>>,
+    depths: Vec<usize>,
+    num_inputs: usize,
+    outputs: Vec<Wire>,
+}
+
+impl<F: Field> Default for CircuitBuilder<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Field> CircuitBuilder<F> {
+    /// Returns an empty circuit.
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            depths: Vec::new(),
+            num_inputs: 0,
+            outputs: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, node: Node<F>, depth: usize) -> Wire {
+        self.nodes.push(node);
+        self.depths.push(depth);
+        Wire(self.nodes.len() - 1)
+    }
+
+    /// Declares a new input. Inputs are numbered in the order of declaration.
+    pub fn input(&mut self) -> Wire {
+        self.num_inputs += 1;
+        self.push(Node::Input(self.num_inputs - 1), 0)
+    }
+
+    /// Returns a wire holding `value`.
+    pub fn constant(&mut self, value: F) -> Wire {
+        self.push(Node::Constant(value), 0)
+    }
+
+    /// Returns a wire holding `left + right`.
+    pub fn add(&mut self, left: Wire, right: Wire) -> Wire {
+        self.gate(GateType::Add, left, right)
+    }
+
+    /// Returns a wire holding `left * right`.
+    pub fn mul(&mut self, left: Wire, right: Wire) -> Wire {
+        self.gate(GateType::Mul, left, right)
+    }
+
+    fn gate(&mut self, gate_type: GateType, left: Wire, right: Wire) -> Wire {
+        let depth = ark_std::cmp::max(self.depths[left.0], self.depths[right.0]) + 1;
+        self.push(Node::Gate(gate_type, left, right), depth)
+    }
+
+    /// Marks `wire` as an output. Outputs are numbered in the order they are marked.
+    pub fn output(&mut self, wire: Wire) {
+        self.outputs.push(wire);
+    }
+
+    /// Compiles the circuit into a layered circuit.
+    pub fn build(mut self) -> CompiledCircuit<F> {
+        assert!(!self.outputs.is_empty(), "circuit has no output");
+        // the output layer is at depth `depth`, and there is at least one layer of gates
+        let depth = self
+            .outputs
+            .iter()
+            .map(|w| self.depths[w.0])
+            .max()
+            .unwrap()
+            .max(1);
+        let one = self.constant(F::one());
+
+        let mut compiler = Compiler {
+            builder: &self,
+            one,
+            layers: vec![Vec::new(); depth + 1],
+            placed: BTreeMap::new(),
+        };
+        // each output has its own gate, so that an output may be marked several times
+        let output_gates: Vec<_> = self
+            .outputs
+            .iter()
+            .map(|&w| compiler.gate_of(w, depth))
+            .collect();
+        compiler.layers[depth] = output_gates;
+        let mut gate_layers = compiler.layers;
+
+        let constants: Vec<_> = self
+            .nodes
+            .iter()
+            .filter_map(|node| match node {
+                Node::Constant(c) => Some(*c),
+                _ => None,
+            })
+            .collect();
+        let input_num_vars = log2_ceil(self.num_inputs + constants.len());
+        let layers = gate_layers
+            .drain(1..)
+            .rev()
+            .map(|gates| Layer {
+                num_vars: log2_ceil(gates.len()),
+                gates,
+            })
+            .collect();
+        CompiledCircuit {
+            circuit: LayeredCircuit {
+                input_num_vars,
+                layers,
+            },
+            num_inputs: self.num_inputs,
+            constants,
+        }
+    }
+}
+
+/// Places the wires of a `CircuitBuilder` in the layers.
+struct Compiler<'a, F: Field> {
+    builder: &'a CircuitBuilder<F>,
+    one: Wire,
+    /// gates of each depth. Depth 0 is the input layer and has no gate.
+    layers: Vec<Vec<Gate>>,
+    /// index of the value of a wire in the layer of some depth
+    placed: BTreeMap<(Wire, usize), usize>,
+}
+
+impl<F: Field> Compiler<'_, F> {
+    /// Returns the index of the value of `wire` in the layer of depth `depth`, adding the gates
+    /// computing it if needed.
+    fn place(&mut self, wire: Wire, depth: usize) -> usize {
+        if let Some(&index) = self.placed.get(&(wire, depth)) {
+            return index;
+        }
+        let index = if depth == 0 {
+            self.input_index(wire)
+        } else {
+            let gate = self.gate_of(wire, depth);
+            self.layers[depth].push(gate);
+            self.layers[depth].len() - 1
+        };
+        self.placed.insert((wire, depth), index);
+        index
+    }
+
+    /// Returns the gate at depth `depth > 0` whose output is the value of `wire`.
+    fn gate_of(&mut self, wire: Wire, depth: usize) -> Gate {
+        let (gate_type, left, right) = match self.builder.nodes[wire.0] {
+            Node::Gate(gate_type, left, right) if self.builder.depths[wire.0] == depth => {
+                (gate_type, left, right)
+            }
+            _ => (GateType::Mul, wire, self.one),
+        };
+        Gate {
+            gate_type,
+            left: self.place(left, depth - 1),
+            right: self.place(right, depth - 1),
+        }
+    }
+
+    /// Returns the index of an input or a constant in the input layer.
+    fn input_index(&self, wire: Wire) -> usize {
+        match self.builder.nodes[wire.0] {
+            Node::Input(i) => i,
+            Node::Constant(_) => {
+                let constant_index = self.builder.nodes[..wire.0]
+                    .iter()
+                    .filter(|node| matches!(node, Node::Constant(_)))
+                    .count();
+                self.builder.num_inputs + constant_index
+            }
+            Node::Gate(..) => unreachable!("gates are not in the input layer"),
+        }
+    }
+}
+
+/// Returns the smallest `n` such that `2^n >= len`.
+fn log2_ceil(len: usize) -> usize {
+    let mut n = 0;
+    while (1 << n) < len {
+        n += 1;
+    }
+    n
+}
+
+/// A layered circuit compiled by `CircuitBuilder`
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompiledCircuit<F: Field> {
+    /// the layered circuit. Its input layer holds the inputs followed by the constants.
+    pub circuit: LayeredCircuit,
+    /// number of inputs declared with `CircuitBuilder::input`
+    pub num_inputs: usize,
+    /// values of the constants, placed in the input layer after the inputs. The last constant is
+    /// the one read by relay gates.
+    pub constants: Vec<F>,
+}
+
+impl<F: Field> CompiledCircuit<F> {
+    /// Returns the values of the input layer for the inputs of the circuit.
+    pub fn input_layer(&self, inputs: &[F]) -> Vec<F> {
+        assert_eq!(inputs.len(), self.num_inputs, "wrong number of inputs");
+        let mut values = inputs.to_vec();
+        values.extend_from_slice(&self.constants);
+        values
+    }
+
+    /// Evaluates the circuit on the inputs, and returns the witness, i.e. the values `V_i` of each
+    /// layer from the output layer to the input layer.
+    pub fn evaluate(&self, inputs: &[F]) -> Vec<DenseMultilinearExtension<F>> {
+        self.circuit.evaluate(&self.input_layer(inputs))
+    }
+
+    /// Returns the outputs of the circuit, in the order they were marked.
+    pub fn outputs(&self, inputs: &[F]) -> Vec<F> {
+        let num_outputs = self.circuit.layers[0].gates.len();
+        let mut outputs = self.evaluate(inputs).swap_remove(0).evaluations;
+        outputs.truncate(num_outputs);
+        outputs
+    }
+}
@@ -0,0 +1,335 @@
+//! Data-parallel GKR for `2^k` copies of the same sub-circuit, following the "copies" optimization
+//! of [Tha13](https://arxiv.org/abs/1304.3812) (Section 5).
+//!
+//! The values of layer i of all copies form a single multilinear extension `V_i(z, c)`, where the
+//! low variables `z` index a value of the sub-circuit and the high variables `c` index the copy.
+//! The wiring predicates are only described for the sub-circuit:
+//!
+//! $$V_i(g, r) = \sum_{c,x,y} eq(r,c)(add(g,x,y)(V_{i+1}(x,c) + V_{i+1}(y,c)) + mult(g,x,y)V_{i+1}(x,c)V_{i+1}(y,c))$$
+//!
+//! Phase one sums over `(x, c)` and binds the copy variables to a new point `r'`. Phase two sums
+//! over y with `V_{i+1}(·, r')`, which is the same sum as the phase two of a single copy. The
+//! prover never materializes a wiring predicate over `3 * (k + s)` variables.
+
+use crate::gkr::data_structures::{
+    GKRCircuitProof, InputLayerSubClaim, LayerProof, LayeredCircuit,
+};
+use crate::gkr::{evaluate_combined_wiring, linear_form, phase_one_tables, phase_two_tables, GKR};
+use crate::gkr_round_sumcheck::claim_reduction::{combine_eq_tables, ClaimReduction};
+use crate::gkr_round_sumcheck::data_structures::GKRProof;
+use crate::gkr_round_sumcheck::GKRRoundSumcheck;
+use crate::ml_sumcheck::protocol::{IPForMLSumcheck, ListOfProductsOfPolynomials};
+use crate::rng::FeedableRNG;
+use crate::utils::{eq_eval, eq_evaluations};
+use ark_ff::Field;
+use ark_poly::{DenseMultilinearExtension, Polynomial};
+use ark_std::marker::PhantomData;
+use ark_std::sync::Arc;
+use ark_std::vec::Vec;
+
+/// `2^log_copies` copies of the same layered sub-circuit evaluated on independent inputs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DataParallelCircuit {
+    /// the circuit applied to each input
+    pub sub_circuit: LayeredCircuit,
+    /// number of variables of the copy index
+    pub log_copies: usize,
+}
+
+impl DataParallelCircuit {
+    /// Returns the inputs of all copies as a single vector, where the inputs of copy `c` start at
+    /// `c * 2^sub_circuit.input_num_vars`.
+    pub fn combine_inputs<F: Field>(&self, inputs: &[Vec<F>]) -> Vec<F> {
+        assert_eq!(inputs.len(), 1 << self.log_copies);
+        let input_size = 1 << self.sub_circuit.input_num_vars;
+        let mut combined = Vec::with_capacity(input_size << self.log_copies);
+        for copy_inputs in inputs {
+            assert!(copy_inputs.len() <= input_size);
+            combined.extend_from_slice(copy_inputs);
+            combined.resize(combined.len() + input_size - copy_inputs.len(), F::zero());
+        }
+        combined
+    }
+
+    /// Evaluates every copy on its inputs, and returns the values of every layer of all copies as
+    /// multilinear extensions, from the output layer to the input layer.
+    pub fn evaluate<F: Field>(&self, inputs: &[Vec<F>]) -> Vec<DenseMultilinearExtension<F>> {
+        assert_eq!(inputs.len(), 1 << self.log_copies);
+        let copies: Vec<_> = inputs
+            .iter()
+            .map(|copy_inputs| self.sub_circuit.evaluate(copy_inputs))
+            .collect();
+        (0..copies[0].len())
+            .map(|i| {
+                let num_vars = copies[0][i].num_vars + self.log_copies;
+                let evaluations = copies
+                    .iter()
+                    .flat_map(|values| values[i].evaluations.iter().copied())
+                    .collect();
+                DenseMultilinearExtension::from_evaluations_vec(num_vars, evaluations)
+            })
+            .collect()
+    }
+}
+
+/// GKR protocol for data-parallel circuits
+pub struct DataParallelGKR<F: Field> {
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> DataParallelGKR<F> {
+    /// Evaluates all copies of the circuit on `inputs` and proves that the outputs are correct.
+    ///
+    /// Returns the outputs of all copies, where the outputs of copy `c` start at
+    /// `c * 2^sub_circuit.layers[0].num_vars`, along with the proof. Returns an error if the
+    /// sub-circuit has no layers, does not pass `LayeredCircuit::check`, or if the inputs do not fit
+    /// the copies.
+    pub fn prove(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        circuit: &DataParallelCircuit,
+        inputs: &[Vec<F>],
+    ) -> Result<(Vec<F>, GKRCircuitProof<F>), crate::Error> {
+        if circuit.sub_circuit.layers.is_empty() {
+            return Err(crate::Error::OtherError("Circuit has no layers.".into()));
+        }
+        circuit.sub_circuit.check()?;
+        let input_size = 1 << circuit.sub_circuit.input_num_vars;
+        if inputs.len() != 1 << circuit.log_copies
+            || inputs
+                .iter()
+                .any(|copy_inputs| copy_inputs.len() > input_size)
+        {
+            return Err(crate::Error::OtherError(
+                "Inputs do not match the copies of the circuit.".into(),
+            ));
+        }
+        let k = circuit.log_copies;
+        let values: Vec<_> = circuit.evaluate(inputs).into_iter().map(Arc::new).collect();
+        let outputs = values[0].evaluations.clone();
+        fs_rng.feed(&outputs)?;
+        let g: Vec<_> = (0..values[0].num_vars).map(|_| F::rand(fs_rng)).collect();
+        let (g_z, r) = g.split_at(values[0].num_vars - k);
+        let mut g_table = eq_evaluations(g_z);
+        let mut r = r.to_vec();
+
+        let layers = &circuit.sub_circuit.layers;
+        let mut layer_proofs = Vec::with_capacity(layers.len());
+        let mut last_points = Vec::new();
+        for i in 0..layers.len() {
+            let below = &values[i + 1];
+            let s = below.num_vars - k;
+            let predicates = circuit.sub_circuit.wiring_predicates(i);
+
+            // phase one: sum over (x, c) of eq(r, c) * (V(x, c) * A(x, c) + B(x, c))
+            let mut a = Vec::with_capacity(1 << below.num_vars);
+            let mut b = Vec::with_capacity(1 << below.num_vars);
+            for copy_values in below.evaluations.chunks(1 << s) {
+                let copy_values = DenseMultilinearExtension::from_evaluations_slice(s, copy_values);
+                let (a_c, b_c) = phase_one_tables(&predicates, &g_table, &copy_values);
+                a.extend(a_c.evaluations);
+                b.extend(b_c.evaluations);
+            }
+            let mut eq_r = Vec::with_capacity(1 << below.num_vars);
+            for e in eq_evaluations(&r) {
+                eq_r.resize(eq_r.len() + (1 << s), e);
+            }
+            let mut poly = ListOfProductsOfPolynomials::new(below.num_vars);
+            let eq_r = Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+                below.num_vars,
+                eq_r,
+            ));
+            poly.add_product(
+                vec![
+                    eq_r.clone(),
+                    below.clone(),
+                    Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+                        below.num_vars,
+                        a,
+                    )),
+                ],
+                F::one(),
+            );
+            poly.add_product(
+                vec![
+                    eq_r,
+                    Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+                        below.num_vars,
+                        b,
+                    )),
+                ],
+                F::one(),
+            );
+            let (phase1_sumcheck_msgs, ux) =
+                GKRRoundSumcheck::prove_phase(fs_rng, below.num_vars, || {
+                    IPForMLSumcheck::prover_init(&poly)
+                });
+            let (u, next_r) = ux.split_at(s);
+            let (u, next_r) = (u.to_vec(), next_r.to_vec());
+            let value_u = below.evaluate(&ux);
+
+            // phase two: sum over y of eq(r, r') * (C(y) * V(y, r') + D(y))
+            let mut below_r = vec![F::zero(); 1 << s];
+            for (copy_values, e) in below
+                .evaluations
+                .chunks(1 << s)
+                .zip(eq_evaluations(&next_r))
+            {
+                for (y, value) in below_r.iter_mut().zip(copy_values) {
+                    *y += e * value;
+                }
+            }
+            let below_r = Arc::new(DenseMultilinearExtension::from_evaluations_vec(s, below_r));
+            let eq_rr = eq_eval(&r, &next_r);
+            let (mut c, mut d) = phase_two_tables(&predicates, &g_table, &u, value_u);
+            c.evaluations.iter_mut().for_each(|x| *x *= eq_rr);
+            d.evaluations.iter_mut().for_each(|x| *x *= eq_rr);
+            let (phase2_sumcheck_msgs, v) = GKRRoundSumcheck::prove_phase(fs_rng, s, || {
+                IPForMLSumcheck::prover_init(&linear_form(c, d, &below_r))
+            });
+            let value_v = below_r.evaluate(&v);
+
+            fs_rng.feed(&[value_u, value_v].to_vec())?;
+            let points = vec![u, v];
+            g_table = combine_eq_tables(&points, &GKR::sample_coefficients(fs_rng))?;
+            last_points = points
+                .into_iter()
+                .map(|p| [p, next_r.clone()].concat())
+                .collect();
+            r = next_r;
+
+            layer_proofs.push(LayerProof {
+                round_proof: GKRProof {
+                    phase1_sumcheck_msgs,
+                    phase2_sumcheck_msgs,
+                },
+                value_u,
+                value_v,
+            });
+        }
+
+        let input = values.last().unwrap();
+        let (input_reduction_proof, _) = ClaimReduction::prove(fs_rng, input, &last_points)?;
+
+        Ok((
+            outputs,
+            GKRCircuitProof {
+                layer_proofs,
+                input_reduction_proof,
+            },
+        ))
+    }
+
+    /// Verifies that `outputs` are the evaluations of all copies of the circuit, and returns a
+    /// subclaim on the combined input layer (see `DataParallelCircuit::combine_inputs`).
+    ///
+    /// If the outputs are correct, `subclaim.verify_subclaim` returns true on the combined inputs.
+    /// Otherwise, it is very likely that `subclaim.verify_subclaim` will return false.
+    ///
+    /// Returns an error if the sub-circuit has no layers, does not pass `LayeredCircuit::check`, or
+    /// if the proof is rejected.
+    pub fn verify(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        circuit: &DataParallelCircuit,
+        outputs: &[F],
+        proof: &GKRCircuitProof<F>,
+    ) -> Result<InputLayerSubClaim<F>, crate::Error> {
+        let k = circuit.log_copies;
+        let layers = &circuit.sub_circuit.layers;
+        if layers.is_empty() {
+            return Err(crate::Error::OtherError("Circuit has no layers.".into()));
+        }
+        circuit.sub_circuit.check()?;
+        if proof.layer_proofs.len() != layers.len() {
+            return Err(crate::Error::Reject(Some(
+                "Number of layer proofs does not match the circuit.".into(),
+            )));
+        }
+        let output_num_vars = layers[0].num_vars + k;
+        if outputs.len() != 1 << output_num_vars {
+            return Err(crate::Error::Reject(Some(
+                "Number of outputs does not match the circuit.".into(),
+            )));
+        }
+        fs_rng.feed(&outputs.to_vec())?;
+        let g: Vec<_> = (0..output_num_vars).map(|_| F::rand(fs_rng)).collect();
+        let mut claim = DenseMultilinearExtension::from_evaluations_slice(output_num_vars, outputs)
+            .evaluate(&g);
+        let (g_z, r) = g.split_at(layers[0].num_vars);
+        let mut points = vec![g_z.to_vec()];
+        let mut coefficients = vec![F::one()];
+        let mut r = r.to_vec();
+
+        let mut last_points = Vec::new();
+        let mut last_values = Vec::new();
+        for (i, layer_proof) in proof.layer_proofs.iter().enumerate() {
+            let s = circuit.sub_circuit.num_vars_below(i);
+            let round_proof = &layer_proof.round_proof;
+            GKRRoundSumcheck::check_phase_shape(
+                "Phase one",
+                s + k,
+                3,
+                &round_proof.phase1_sumcheck_msgs,
+            )?;
+            GKRRoundSumcheck::check_phase_shape(
+                "Phase two",
+                s,
+                2,
+                &round_proof.phase2_sumcheck_msgs,
+            )?;
+
+            let phase1_subclaim = GKRRoundSumcheck::verify_phase(
+                fs_rng,
+                s + k,
+                3,
+                &round_proof.phase1_sumcheck_msgs,
+                claim,
+            )?;
+            let phase2_subclaim = GKRRoundSumcheck::verify_phase(
+                fs_rng,
+                s,
+                2,
+                &round_proof.phase2_sumcheck_msgs,
+                phase1_subclaim.expected_evaluation,
+            )?;
+            let (u, next_r) = phase1_subclaim.point.split_at(s);
+            let (u, next_r) = (u.to_vec(), next_r.to_vec());
+            let v = phase2_subclaim.point;
+            let (value_u, value_v) = (layer_proof.value_u, layer_proof.value_v);
+
+            let eq_rr = eq_eval(&r, &next_r);
+            let (add, mult) = circuit.sub_circuit.wiring_predicates(i);
+            let add = evaluate_combined_wiring(&add, &points, &coefficients, &u, &v);
+            let mult = evaluate_combined_wiring(&mult, &points, &coefficients, &u, &v);
+            if eq_rr * (add * (value_u + value_v) + mult * value_u * value_v)
+                != phase2_subclaim.expected_evaluation
+            {
+                return Err(crate::Error::Reject(Some(format!(
+                    "Layer {} is not consistent with the claimed values of the layer below.",
+                    i
+                ))));
+            }
+
+            fs_rng.feed(&[value_u, value_v].to_vec())?;
+            coefficients = GKR::sample_coefficients(fs_rng);
+            points = vec![u, v];
+            claim = coefficients[0] * value_u + coefficients[1] * value_v;
+            last_points = points
+                .iter()
+                .map(|p| [p.as_slice(), &next_r].concat())
+                .collect();
+            last_values = vec![value_u, value_v];
+            r = next_r;
+        }
+
+        let reduced = ClaimReduction::verify(
+            fs_rng,
+            &last_points,
+            &last_values,
+            &proof.input_reduction_proof,
+        )?;
+        Ok(InputLayerSubClaim {
+            point: reduced.point,
+            expected_evaluation: reduced.expected_evaluation,
+        })
+    }
+}
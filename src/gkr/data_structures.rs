@@ -0,0 +1,209 @@
+//! Data structures used by the GKR protocol for layered circuits
+
+use crate::gkr_round_sumcheck::claim_reduction::ClaimReductionProof;
+use crate::gkr_round_sumcheck::data_structures::{GKRProof, GateList};
+use crate::pcs::MultilinearPCS;
+use ark_ff::Field;
+use ark_poly::{DenseMultilinearExtension, Polynomial};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::vec::Vec;
+
+/// Type of a gate in a layered arithmetic circuit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GateType {
+    /// outputs `left + right`
+    Add,
+    /// outputs `left * right`
+    Mul,
+}
+
+/// A fan-in two gate reading two values of the layer below.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Gate {
+    /// type of the gate
+    pub gate_type: GateType,
+    /// index of the left input in the layer below
+    pub left: usize,
+    /// index of the right input in the layer below
+    pub right: usize,
+}
+
+/// A layer of a layered arithmetic circuit. The i-th gate outputs the i-th value of the layer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Layer {
+    /// number of variables of the layer, i.e. the layer holds `2^num_vars` values
+    pub num_vars: usize,
+    /// gates of this layer. Values past `gates.len()` are zero.
+    pub gates: Vec<Gate>,
+}
+
+impl Layer {
+    /// Returns an empty layer with `2^num_vars` values.
+    pub fn new(num_vars: usize) -> Self {
+        Self {
+            num_vars,
+            gates: Vec::new(),
+        }
+    }
+
+    /// Add a gate to this layer and returns its output index.
+    pub fn add_gate(&mut self, gate_type: GateType, left: usize, right: usize) -> usize {
+        assert!(self.gates.len() < 1 << self.num_vars, "layer is full");
+        self.gates.push(Gate {
+            gate_type,
+            left,
+            right,
+        });
+        self.gates.len() - 1
+    }
+}
+
+/// A layered arithmetic circuit made of add and mult gates.
+///
+/// `layers[0]` is the output layer, and gates of `layers[i]` read the values of `layers[i + 1]`.
+/// Gates of the last layer read the input of the circuit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LayeredCircuit {
+    /// number of variables of the input layer
+    pub input_num_vars: usize,
+    /// layers of the circuit, from the output layer to the layer right above the input
+    pub layers: Vec<Layer>,
+}
+
+impl LayeredCircuit {
+    /// Returns the number of variables of the layer read by `layers[i]`.
+    pub fn num_vars_below(&self, i: usize) -> usize {
+        self.layers
+            .get(i + 1)
+            .map_or(self.input_num_vars, |layer| layer.num_vars)
+    }
+
+    /// Checks that each layer has at most `2^num_vars` gates, and that each gate reads a value of
+    /// the layer below. Returns an error otherwise.
+    pub fn check(&self) -> Result<(), crate::Error> {
+        for (i, layer) in self.layers.iter().enumerate() {
+            if layer.gates.len() > 1 << layer.num_vars {
+                return Err(crate::Error::OtherError(format!(
+                    "Layer {} has more gates than values.",
+                    i
+                )));
+            }
+            let below_size = 1 << self.num_vars_below(i);
+            if layer
+                .gates
+                .iter()
+                .any(|gate| gate.left >= below_size || gate.right >= below_size)
+            {
+                return Err(crate::Error::OtherError(format!(
+                    "Layer {} has a gate reading past the layer below.",
+                    i
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the wiring predicates `(add, mult)` of `layers[i]` as gate lists over
+    /// `(g, x, y)`, in the format consumed by `GKRRoundSumcheck::prove_with_gates`. Use
+    /// `GateList::to_sparse_mle` to get the `f1` of `GKRRoundSumcheck` and `GKRFoldInstance`.
+    pub fn wiring_predicates<F: Field>(&self, i: usize) -> (GateList<F>, GateList<F>) {
+        let layer = &self.layers[i];
+        let below = self.num_vars_below(i);
+        let mut add = GateList::new(layer.num_vars, below, below);
+        let mut mult = GateList::new(layer.num_vars, below, below);
+        for (z, gate) in layer.gates.iter().enumerate() {
+            let predicate = match gate.gate_type {
+                GateType::Add => &mut add,
+                GateType::Mul => &mut mult,
+            };
+            predicate.add_gate(z, gate.left, gate.right, F::one());
+        }
+        (add, mult)
+    }
+
+    /// Evaluates the circuit on `inputs`, and returns the values of every layer as multilinear
+    /// extensions, from the output layer to the input layer.
+    ///
+    /// Panics if the circuit does not pass `check`.
+    pub fn evaluate<F: Field>(&self, inputs: &[F]) -> Vec<DenseMultilinearExtension<F>> {
+        assert!(
+            inputs.len() <= 1 << self.input_num_vars,
+            "too many inputs for the input layer"
+        );
+        let mut input_values = inputs.to_vec();
+        input_values.resize(1 << self.input_num_vars, F::zero());
+        let mut values = vec![DenseMultilinearExtension::from_evaluations_vec(
+            self.input_num_vars,
+            input_values,
+        )];
+        for layer in self.layers.iter().rev() {
+            let below = values.last().unwrap();
+            let mut layer_values = vec![F::zero(); 1 << layer.num_vars];
+            for (out, gate) in layer.gates.iter().enumerate() {
+                let (left, right) = (below[gate.left], below[gate.right]);
+                layer_values[out] = match gate.gate_type {
+                    GateType::Add => left + right,
+                    GateType::Mul => left * right,
+                };
+            }
+            values.push(DenseMultilinearExtension::from_evaluations_vec(
+                layer.num_vars,
+                layer_values,
+            ));
+        }
+        values.reverse();
+        values
+    }
+}
+
+/// Proof of a single layer of the GKR protocol
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct LayerProof<F: Field> {
+    /// sumcheck proofs over x and over y
+    pub round_proof: GKRProof<F>,
+    /// claimed evaluation of the layer below at `u`
+    pub value_u: F,
+    /// claimed evaluation of the layer below at `v`
+    pub value_v: F,
+}
+
+/// Proof of the evaluation of a layered circuit, with one `LayerProof` per layer from the output
+/// layer to the input layer.
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct GKRCircuitProof<F: Field> {
+    /// proofs of each layer
+    pub layer_proofs: Vec<LayerProof<F>>,
+    /// reduction of the two claims on the input layer to one
+    pub input_reduction_proof: ClaimReductionProof<F>,
+}
+
+/// Subclaim on the input layer when the verifier is convinced
+pub struct InputLayerSubClaim<F: Field> {
+    /// the point that the multilinear extension of the input is evaluated to
+    pub point: Vec<F>,
+    /// the expected evaluation
+    pub expected_evaluation: F,
+}
+
+impl<F: Field> InputLayerSubClaim<F> {
+    /// Verify that the subclaim is true by evaluating the multilinear extension of `inputs`.
+    pub fn verify_subclaim(&self, inputs: &[F]) -> bool {
+        let num_vars = self.point.len();
+        assert!(inputs.len() <= 1 << num_vars);
+        let mut evaluations = inputs.to_vec();
+        evaluations.resize(1 << num_vars, F::zero());
+        let input = DenseMultilinearExtension::from_evaluations_vec(num_vars, evaluations);
+        input.evaluate(&self.point) == self.expected_evaluation
+    }
+
+    /// Verify that the subclaim is true using an opening `proof` of the input committed in
+    /// `commitment`, so that the verifier does not need to read the input.
+    pub fn verify_with_pcs<P: MultilinearPCS<F>>(
+        &self,
+        pp: &P::Parameters,
+        commitment: &P::Commitment,
+        proof: &P::Proof,
+    ) -> Result<bool, crate::Error> {
+        P::verify(pp, commitment, &self.point, self.expected_evaluation, proof)
+    }
+}
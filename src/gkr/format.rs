@@ -0,0 +1,207 @@
+//! Line-oriented text format for layered arithmetic circuits.
+//!
+//! ```text
+//! # (a + b) * (c * d) + 5, where 5 is relayed by `mul 2 4 5` and `mul 1 2 3`
+//! inputs 3 4      # number of variables of the input layer, number of inputs
+//! constant 5      # constants follow the inputs in the input layer
+//! constant 1
+//! layer 0         # layers go from the output layer to the layer right above the input
+//! add 0 0 1       # gate type, output index, left input index, right input index
+//! layer 1
+//! mul 0 0 1
+//! mul 1 2 3
+//! layer 2
+//! add 0 0 1
+//! mul 1 2 3
+//! mul 2 4 5
+//! mul 3 5 5       # the constant one is relayed as well
+//! ```
+//!
+//! Blank lines and everything following `#` are ignored. The gates of a layer may be listed in
+//! any order, but their output indices must be `0, 1, ..., n - 1`. Constants are written in
+//! decimal. Layers have at most `MAX_NUM_VARS` variables.
+
+use crate::gkr::builder::CompiledCircuit;
+use crate::gkr::data_structures::{Gate, GateType, Layer, LayeredCircuit};
+use crate::Error;
+use ark_ff::PrimeField;
+use ark_std::fmt::Write;
+use ark_std::str::FromStr;
+use ark_std::string::String;
+use ark_std::vec::Vec;
+
+/// Maximum number of variables of a layer in the text format
+pub const MAX_NUM_VARS: usize = 30;
+
+fn parse_error(line: usize, message: &str) -> Error {
+    Error::OtherError(format!("line {}: {}", line, message))
+}
+
+fn parse_usize(line: usize, token: Option<&str>) -> Result<usize, Error> {
+    token
+        .ok_or_else(|| parse_error(line, "missing number"))?
+        .parse()
+        .map_err(|_| parse_error(line, "invalid number"))
+}
+
+fn parse_num_vars(line: usize, token: Option<&str>) -> Result<usize, Error> {
+    let num_vars = parse_usize(line, token)?;
+    if num_vars > MAX_NUM_VARS {
+        return Err(parse_error(line, "too many variables"));
+    }
+    Ok(num_vars)
+}
+
+/// Parses a circuit in the text format.
+pub fn parse_circuit<F: PrimeField>(text: &str) -> Result<CompiledCircuit<F>, Error> {
+    let mut inputs = None;
+    let mut constants = Vec::new();
+    // layers with their gates as (output index, gate)
+    let mut layers: Vec<(usize, Vec<(usize, Gate)>)> = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line_number = i + 1;
+        let content = line.split('#').next().unwrap();
+        let mut tokens = content.split_whitespace();
+        let keyword = match tokens.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+        match keyword {
+            "inputs" => {
+                if inputs.is_some() {
+                    return Err(parse_error(line_number, "inputs declared twice"));
+                }
+                let num_vars = parse_num_vars(line_number, tokens.next())?;
+                let num_inputs = parse_usize(line_number, tokens.next())?;
+                inputs = Some((num_vars, num_inputs));
+            }
+            "constant" => {
+                let token = tokens
+                    .next()
+                    .ok_or_else(|| parse_error(line_number, "missing constant"))?;
+                let value =
+                    F::from_str(token).map_err(|_| parse_error(line_number, "invalid constant"))?;
+                constants.push(value);
+            }
+            "layer" => {
+                let num_vars = parse_num_vars(line_number, tokens.next())?;
+                layers.push((num_vars, Vec::new()));
+            }
+            "add" | "mul" => {
+                let gate_type = if keyword == "add" {
+                    GateType::Add
+                } else {
+                    GateType::Mul
+                };
+                let out = parse_usize(line_number, tokens.next())?;
+                let left = parse_usize(line_number, tokens.next())?;
+                let right = parse_usize(line_number, tokens.next())?;
+                let (num_vars, gates) = layers
+                    .last_mut()
+                    .ok_or_else(|| parse_error(line_number, "gate outside of a layer"))?;
+                if out >= 1 << *num_vars {
+                    return Err(parse_error(line_number, "output index out of range"));
+                }
+                gates.push((
+                    out,
+                    Gate {
+                        gate_type,
+                        left,
+                        right,
+                    },
+                ));
+            }
+            _ => return Err(parse_error(line_number, "unknown keyword")),
+        }
+        if tokens.next().is_some() {
+            return Err(parse_error(line_number, "unexpected token"));
+        }
+    }
+
+    let (input_num_vars, num_inputs) =
+        inputs.ok_or_else(|| Error::OtherError("missing inputs declaration".into()))?;
+    let input_len = num_inputs.checked_add(constants.len());
+    if input_len
+        .filter(|&len| len <= 1 << input_num_vars)
+        .is_none()
+    {
+        return Err(Error::OtherError(
+            "too many inputs and constants for the input layer".into(),
+        ));
+    }
+    if layers.is_empty() {
+        return Err(Error::OtherError("circuit has no layer".into()));
+    }
+    let layers: Vec<_> = layers
+        .into_iter()
+        .enumerate()
+        .map(|(i, (num_vars, mut gates))| {
+            gates.sort_by_key(|(out, _)| *out);
+            if gates.iter().enumerate().any(|(j, (out, _))| *out != j) {
+                return Err(Error::OtherError(format!(
+                    "layer {}: output indices are not 0, 1, ..., n - 1",
+                    i
+                )));
+            }
+            Ok(Layer {
+                num_vars,
+                gates: gates.into_iter().map(|(_, gate)| gate).collect(),
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    let circuit = LayeredCircuit {
+        input_num_vars,
+        layers,
+    };
+    for (i, layer) in circuit.layers.iter().enumerate() {
+        let below = 1 << circuit.num_vars_below(i);
+        if layer
+            .gates
+            .iter()
+            .any(|g| g.left >= below || g.right >= below)
+        {
+            return Err(Error::OtherError(format!(
+                "layer {}: input index out of range",
+                i
+            )));
+        }
+    }
+    Ok(CompiledCircuit {
+        circuit,
+        num_inputs,
+        constants,
+    })
+}
+
+/// Writes a circuit in the text format.
+pub fn write_circuit<F: PrimeField>(circuit: &CompiledCircuit<F>) -> String {
+    let mut text = String::new();
+    writeln!(
+        text,
+        "inputs {} {}",
+        circuit.circuit.input_num_vars, circuit.num_inputs
+    )
+    .unwrap();
+    for constant in &circuit.constants {
+        writeln!(text, "constant {}", constant.into_bigint()).unwrap();
+    }
+    for layer in &circuit.circuit.layers {
+        writeln!(text, "layer {}", layer.num_vars).unwrap();
+        for (out, gate) in layer.gates.iter().enumerate() {
+            let keyword = match gate.gate_type {
+                GateType::Add => "add",
+                GateType::Mul => "mul",
+            };
+            writeln!(text, "{} {} {} {}", keyword, out, gate.left, gate.right).unwrap();
+        }
+    }
+    text
+}
+
+impl<F: PrimeField> FromStr for CompiledCircuit<F> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_circuit(s)
+    }
+}
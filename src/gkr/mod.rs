@@ -0,0 +1,262 @@
+//! Implementation of the GKR protocol for layered arithmetic circuits, as described in
+//! [XZZPS19](https://eprint.iacr.org/2019/317.pdf#subsection.3.3) (Section 3.3).
+//!
+//! The values of layer i are defined by the values of layer i + 1 as
+//!
+//! $$V_i(g) = \sum_{x,y} add_{i+1}(g,x,y)(V_{i+1}(x) + V_{i+1}(y)) + mult_{i+1}(g,x,y)V_{i+1}(x)V_{i+1}(y)$$
+//!
+//! The prover goes from the output layer to the input layer, reducing a claim on `V_i` to claims on
+//! `V_{i+1}(u)` and `V_{i+1}(v)` with the two-phase sumcheck of `gkr_round_sumcheck`, whose phases
+//! are initialized from the gate lists of the wiring predicates.
+//! Claims on `V_{i+1}(u)` and `V_{i+1}(v)` are combined by a random linear combination of `eq(u, ·)`
+//! and `eq(v, ·)` for the next layer, so that each layer only needs one round sumcheck. The two
+//! claims on the input layer are reduced to one with `ClaimReduction`.
+
+pub mod builder;
+pub mod data_parallel;
+pub mod data_structures;
+pub mod format;
+#[cfg(test)]
+mod test;
+
+use crate::gkr::data_structures::{
+    GKRCircuitProof, InputLayerSubClaim, LayerProof, LayeredCircuit,
+};
+use crate::gkr_round_sumcheck::claim_reduction::{combine_eq_tables, ClaimReduction};
+use crate::gkr_round_sumcheck::data_structures::{GKRProof, GateList};
+use crate::gkr_round_sumcheck::wiring::WiringPredicate;
+use crate::gkr_round_sumcheck::{
+    initialize_phase_one_with_table, initialize_phase_two_with_gates, GKRRoundSumcheck,
+};
+use crate::ml_sumcheck::protocol::{IPForMLSumcheck, ListOfProductsOfPolynomials};
+use crate::rng::FeedableRNG;
+use crate::utils::eq_evaluations;
+use ark_ff::{Field, Zero};
+use ark_poly::{DenseMultilinearExtension, Polynomial};
+use ark_std::marker::PhantomData;
+use ark_std::sync::Arc;
+use ark_std::vec::Vec;
+
+/// Takes the wiring predicates `(add, mult)` of a layer, the table of the combined claim weights
+/// over its gates and the values of the layer below. Returns `(A, B)` such that the phase one sum
+/// over x is `A(x)V(x) + B(x)`.
+///
+/// * `A(x) = sum_{z,y} mult(z,x,y)G(z)V(y) + add(z,x,y)G(z)`
+/// * `B(x) = sum_{z,y} add(z,x,y)G(z)V(y)`
+fn phase_one_tables<F: Field>(
+    (add, mult): &(GateList<F>, GateList<F>),
+    g_table: &[F],
+    below: &DenseMultilinearExtension<F>,
+) -> (DenseMultilinearExtension<F>, DenseMultilinearExtension<F>) {
+    let ones = DenseMultilinearExtension::from_evaluations_vec(
+        below.num_vars,
+        vec![F::one(); 1 << below.num_vars],
+    );
+    let mut a = initialize_phase_one_with_table(mult, below, g_table);
+    a += &initialize_phase_one_with_table(add, &ones, g_table);
+    (a, initialize_phase_one_with_table(add, below, g_table))
+}
+
+/// Takes the wiring predicates `(add, mult)` of a layer, the table of the combined claim weights
+/// over its gates, phase one randomness u and V(u). Returns `(C, D)` such that the phase two sum
+/// over y is `C(y)V(y) + D(y)`.
+///
+/// * `C(y) = sum_{z,x} mult(z,x,y)G(z)eq(u,x)V(u) + add(z,x,y)G(z)eq(u,x)`
+/// * `D(y) = sum_{z,x} add(z,x,y)G(z)eq(u,x)V(u)`
+fn phase_two_tables<F: Field>(
+    (add, mult): &(GateList<F>, GateList<F>),
+    g_table: &[F],
+    u: &[F],
+    value_u: F,
+) -> (DenseMultilinearExtension<F>, DenseMultilinearExtension<F>) {
+    let add_u = initialize_phase_two_with_gates(add, g_table, u);
+    let mut c = add_u.clone();
+    c += (value_u, &initialize_phase_two_with_gates(mult, g_table, u));
+    let mut d = DenseMultilinearExtension::zero();
+    d += (value_u, &add_u);
+    (c, d)
+}
+
+/// Evaluates a wiring predicate at `(G, u, v)`, where the claim weights are
+/// `G = sum_j coefficients[j] eq(points[j], ·)`. The predicate is linear in `eq(g, ·)`, so this is
+/// the same combination of its evaluations at each point.
+fn evaluate_combined_wiring<F: Field>(
+    predicate: &GateList<F>,
+    points: &[Vec<F>],
+    coefficients: &[F],
+    u: &[F],
+    v: &[F],
+) -> F {
+    points
+        .iter()
+        .zip(coefficients)
+        .map(|(g, &coefficient)| coefficient * predicate.evaluate_wiring(g, u, v))
+        .sum()
+}
+
+/// Returns `(1, [a, values]) + (1, [b])` as a list of products.
+fn linear_form<F: Field>(
+    a: DenseMultilinearExtension<F>,
+    b: DenseMultilinearExtension<F>,
+    values: &Arc<DenseMultilinearExtension<F>>,
+) -> ListOfProductsOfPolynomials<F> {
+    let mut poly = ListOfProductsOfPolynomials::new(values.num_vars);
+    poly.add_product(vec![Arc::new(a), values.clone()], F::one());
+    poly.add_product(vec![Arc::new(b)], F::one());
+    poly
+}
+
+/// GKR protocol for layered arithmetic circuits
+pub struct GKR<F: Field> {
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> GKR<F> {
+    /// Evaluates `circuit` on `inputs` and proves that the outputs are correct.
+    ///
+    /// Returns the outputs of the circuit along with the proof, or an error if the circuit has no
+    /// layers, does not pass `LayeredCircuit::check`, or if there are more inputs than values in the
+    /// input layer.
+    pub fn prove(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        circuit: &LayeredCircuit,
+        inputs: &[F],
+    ) -> Result<(Vec<F>, GKRCircuitProof<F>), crate::Error> {
+        if circuit.layers.is_empty() {
+            return Err(crate::Error::OtherError("Circuit has no layers.".into()));
+        }
+        circuit.check()?;
+        if inputs.len() > 1 << circuit.input_num_vars {
+            return Err(crate::Error::OtherError(
+                "Too many inputs for the input layer.".into(),
+            ));
+        }
+        let values: Vec<_> = circuit.evaluate(inputs).into_iter().map(Arc::new).collect();
+        let outputs = values[0].evaluations.clone();
+        fs_rng.feed(&outputs)?;
+        let g: Vec<_> = (0..circuit.layers[0].num_vars)
+            .map(|_| F::rand(fs_rng))
+            .collect();
+        let mut g_table = eq_evaluations(&g);
+
+        let mut layer_proofs = Vec::with_capacity(circuit.layers.len());
+        let mut last_points = Vec::new();
+        for i in 0..circuit.layers.len() {
+            let below = &values[i + 1];
+            let predicates = circuit.wiring_predicates(i);
+
+            let (a, b) = phase_one_tables(&predicates, &g_table, below);
+            let dim = below.num_vars;
+            let (phase1_sumcheck_msgs, u) = GKRRoundSumcheck::prove_phase(fs_rng, dim, || {
+                IPForMLSumcheck::prover_init(&linear_form(a, b, below))
+            });
+            let value_u = below.evaluate(&u);
+
+            let (c, d) = phase_two_tables(&predicates, &g_table, &u, value_u);
+            let (phase2_sumcheck_msgs, v) = GKRRoundSumcheck::prove_phase(fs_rng, dim, || {
+                IPForMLSumcheck::prover_init(&linear_form(c, d, below))
+            });
+            let value_v = below.evaluate(&v);
+
+            fs_rng.feed(&[value_u, value_v].to_vec())?;
+            let points = vec![u, v];
+            g_table = combine_eq_tables(&points, &Self::sample_coefficients(fs_rng))?;
+            last_points = points;
+
+            layer_proofs.push(LayerProof {
+                round_proof: GKRProof {
+                    phase1_sumcheck_msgs,
+                    phase2_sumcheck_msgs,
+                },
+                value_u,
+                value_v,
+            });
+        }
+
+        let input = values.last().unwrap();
+        let (input_reduction_proof, _) = ClaimReduction::prove(fs_rng, input, &last_points)?;
+
+        Ok((
+            outputs,
+            GKRCircuitProof {
+                layer_proofs,
+                input_reduction_proof,
+            },
+        ))
+    }
+
+    /// Verifies that `outputs` is the evaluation of `circuit`, and returns a subclaim on the input
+    /// layer.
+    ///
+    /// If the outputs are correct, `subclaim.verify_subclaim` returns true on the inputs.
+    /// Otherwise, it is very likely that `subclaim.verify_subclaim` will return false.
+    ///
+    /// Returns an error if the circuit has no layers, does not pass `LayeredCircuit::check`, or if
+    /// the proof is rejected.
+    pub fn verify(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        circuit: &LayeredCircuit,
+        outputs: &[F],
+        proof: &GKRCircuitProof<F>,
+    ) -> Result<InputLayerSubClaim<F>, crate::Error> {
+        if circuit.layers.is_empty() {
+            return Err(crate::Error::OtherError("Circuit has no layers.".into()));
+        }
+        circuit.check()?;
+        if proof.layer_proofs.len() != circuit.layers.len() {
+            return Err(crate::Error::Reject(Some(
+                "Number of layer proofs does not match the circuit.".into(),
+            )));
+        }
+        let output_num_vars = circuit.layers[0].num_vars;
+        if outputs.len() != 1 << output_num_vars {
+            return Err(crate::Error::Reject(Some(
+                "Number of outputs does not match the circuit.".into(),
+            )));
+        }
+        fs_rng.feed(&outputs.to_vec())?;
+        let g: Vec<_> = (0..output_num_vars).map(|_| F::rand(fs_rng)).collect();
+        let mut claim = DenseMultilinearExtension::from_evaluations_slice(output_num_vars, outputs)
+            .evaluate(&g);
+        let mut points = vec![g];
+        let mut coefficients = vec![F::one()];
+
+        let mut last_values = Vec::new();
+        for (i, layer_proof) in proof.layer_proofs.iter().enumerate() {
+            let dim = circuit.num_vars_below(i);
+            let subclaim =
+                GKRRoundSumcheck::verify(fs_rng, dim, dim, &layer_proof.round_proof, claim)?;
+            let (u, v) = (subclaim.u, subclaim.v);
+            let (value_u, value_v) = (layer_proof.value_u, layer_proof.value_v);
+
+            let (add, mult) = circuit.wiring_predicates(i);
+            let add = evaluate_combined_wiring(&add, &points, &coefficients, &u, &v);
+            let mult = evaluate_combined_wiring(&mult, &points, &coefficients, &u, &v);
+            if add * (value_u + value_v) + mult * value_u * value_v != subclaim.expected_evaluation
+            {
+                return Err(crate::Error::Reject(Some(format!(
+                    "Layer {} is not consistent with the claimed values of the layer below.",
+                    i
+                ))));
+            }
+
+            fs_rng.feed(&[value_u, value_v].to_vec())?;
+            coefficients = Self::sample_coefficients(fs_rng);
+            points = vec![u, v];
+            claim = coefficients[0] * value_u + coefficients[1] * value_v;
+            last_values = vec![value_u, value_v];
+        }
+
+        let reduced =
+            ClaimReduction::verify(fs_rng, &points, &last_values, &proof.input_reduction_proof)?;
+        Ok(InputLayerSubClaim {
+            point: reduced.point,
+            expected_evaluation: reduced.expected_evaluation,
+        })
+    }
+
+    /// Samples the coefficients `alpha` and `beta` that combine the claims on `V(u)` and `V(v)`.
+    fn sample_coefficients<R: FeedableRNG>(rng: &mut R) -> Vec<F> {
+        vec![F::rand(rng), F::rand(rng)]
+    }
+}
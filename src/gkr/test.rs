@@ -0,0 +1,447 @@
+use crate::gkr::builder::{CircuitBuilder, CompiledCircuit};
+use crate::gkr::data_parallel::{DataParallelCircuit, DataParallelGKR};
+use crate::gkr::data_structures::{GateType, Layer, LayeredCircuit};
+use crate::gkr::format::{parse_circuit, write_circuit};
+use crate::gkr::GKR;
+use crate::gkr_round_sumcheck::GKRRoundSumcheck;
+use crate::pcs::kzg::MultilinearKZG;
+use crate::pcs::transparent::TransparentPCS;
+use crate::pcs::MultilinearPCS;
+use crate::rng::{Blake2b512Rng, FeedableRNG};
+use ark_ff::Field;
+use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+use ark_std::rand::{Rng, RngCore};
+use ark_std::vec::Vec;
+use ark_std::{test_rng, UniformRand};
+use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+/// Builds a random layered circuit whose layers have the given number of variables, from the output
+/// layer to the input layer.
+fn random_circuit<R: RngCore>(num_vars: &[usize], rng: &mut R) -> LayeredCircuit {
+    let (input_num_vars, layer_num_vars) = num_vars.split_last().unwrap();
+    let layers = layer_num_vars
+        .iter()
+        .enumerate()
+        .map(|(i, &nv)| {
+            let below = num_vars[i + 1];
+            let mut layer = Layer::new(nv);
+            for _ in 0..(1 << nv) {
+                let gate_type = if rng.gen() {
+                    GateType::Add
+                } else {
+                    GateType::Mul
+                };
+                layer.add_gate(
+                    gate_type,
+                    rng.gen_range(0..1 << below),
+                    rng.gen_range(0..1 << below),
+                );
+            }
+            layer
+        })
+        .collect();
+    LayeredCircuit {
+        input_num_vars: *input_num_vars,
+        layers,
+    }
+}
+
+fn test_circuit<F: Field>(num_vars: &[usize]) {
+    let mut rng = test_rng();
+    let circuit = random_circuit(num_vars, &mut rng);
+    let inputs: Vec<_> = (0..1 << circuit.input_num_vars)
+        .map(|_| F::rand(&mut rng))
+        .collect();
+
+    let mut fs_rng = Blake2b512Rng::setup();
+    let (outputs, proof) = GKR::prove(&mut fs_rng, &circuit, &inputs).expect("fail to prove");
+    assert_eq!(outputs, circuit.evaluate(&inputs)[0].evaluations);
+
+    let mut fs_rng = Blake2b512Rng::setup();
+    let subclaim =
+        GKR::verify(&mut fs_rng, &circuit, &outputs, &proof).expect("verification failed");
+    assert!(subclaim.verify_subclaim(&inputs));
+}
+
+#[test]
+fn test_evaluate() {
+    // (a + b) * (c * d)
+    let mut output = Layer::new(0);
+    output.add_gate(GateType::Mul, 0, 1);
+    let mut middle = Layer::new(1);
+    middle.add_gate(GateType::Add, 0, 1);
+    middle.add_gate(GateType::Mul, 2, 3);
+    let circuit = LayeredCircuit {
+        input_num_vars: 2,
+        layers: vec![output, middle],
+    };
+    let inputs: Vec<_> = [2u64, 3, 4, 5].iter().map(|&x| Fr::from(x)).collect();
+    let values = circuit.evaluate(&inputs);
+    assert_eq!(values.len(), 3);
+    assert_eq!(values[0].evaluations, vec![Fr::from(100u64)]);
+    assert_eq!(values[1].evaluations, vec![Fr::from(5u64), Fr::from(20u64)]);
+    assert_eq!(values[2].evaluations, inputs);
+}
+
+#[test]
+fn test_same_width() {
+    test_circuit::<Fr>(&[4, 4, 4, 4]);
+}
+
+#[test]
+fn test_different_widths() {
+    test_circuit::<Fr>(&[1, 3, 6, 2, 5]);
+}
+
+#[test]
+fn test_wrong_output_fails() {
+    let mut rng = test_rng();
+    let circuit = random_circuit(&[3, 4, 5], &mut rng);
+    let inputs: Vec<_> = (0..1 << 5).map(|_| Fr::rand(&mut rng)).collect();
+
+    let mut fs_rng = Blake2b512Rng::setup();
+    let (mut outputs, proof) = GKR::prove(&mut fs_rng, &circuit, &inputs).unwrap();
+    outputs[2] += Fr::from(1u64);
+
+    let mut fs_rng = Blake2b512Rng::setup();
+    assert!(GKR::verify(&mut fs_rng, &circuit, &outputs, &proof).is_err());
+}
+
+#[test]
+fn test_wrong_input_fails() {
+    let mut rng = test_rng();
+    let circuit = random_circuit(&[3, 4, 5], &mut rng);
+    let mut inputs: Vec<_> = (0..1 << 5).map(|_| Fr::rand(&mut rng)).collect();
+
+    let mut fs_rng = Blake2b512Rng::setup();
+    let (outputs, proof) = GKR::prove(&mut fs_rng, &circuit, &inputs).unwrap();
+    inputs[7] += Fr::from(1u64);
+
+    let mut fs_rng = Blake2b512Rng::setup();
+    let subclaim = GKR::verify(&mut fs_rng, &circuit, &outputs, &proof).unwrap();
+    assert!(!subclaim.verify_subclaim(&inputs));
+}
+
+#[test]
+fn test_circuit_errors() {
+    let mut rng = test_rng();
+    let empty = LayeredCircuit {
+        input_num_vars: 2,
+        layers: Vec::new(),
+    };
+    let inputs = vec![Fr::from(1u64); 4];
+    let mut fs_rng = Blake2b512Rng::setup();
+    assert!(GKR::prove(&mut fs_rng, &empty, &inputs).is_err());
+
+    let circuit = random_circuit(&[1, 2], &mut rng);
+    let mut fs_rng = Blake2b512Rng::setup();
+    let (outputs, proof) = GKR::prove(&mut fs_rng, &circuit, &inputs).unwrap();
+    let mut fs_rng = Blake2b512Rng::setup();
+    assert!(GKR::verify(&mut fs_rng, &empty, &outputs, &proof).is_err());
+
+    let mut fs_rng = Blake2b512Rng::setup();
+    assert!(GKR::prove(&mut fs_rng, &circuit, &[Fr::from(1u64); 5]).is_err());
+
+    // gates reading past the layer below are errors for both the prover and the verifier
+    let mut out_of_bounds = circuit.clone();
+    out_of_bounds.layers[0].gates[0].right = 4;
+    let mut fs_rng = Blake2b512Rng::setup();
+    assert!(GKR::prove(&mut fs_rng, &out_of_bounds, &inputs).is_err());
+    let mut fs_rng = Blake2b512Rng::setup();
+    assert!(GKR::verify(&mut fs_rng, &out_of_bounds, &outputs, &proof).is_err());
+    let mut too_many_gates = circuit;
+    let gate = too_many_gates.layers[0].gates[0];
+    too_many_gates.layers[0].gates.push(gate);
+    let mut fs_rng = Blake2b512Rng::setup();
+    assert!(GKR::prove(&mut fs_rng, &too_many_gates, &inputs).is_err());
+}
+
+#[test]
+fn test_zero_variable_layers() {
+    let mut rng = test_rng();
+    // a one-gate output layer, a layer over one variable, and a single input
+    for num_vars in [vec![0, 1, 2], vec![1, 0, 1], vec![2, 1, 0], vec![0, 0]] {
+        let circuit = random_circuit(&num_vars, &mut rng);
+        let inputs: Vec<_> = (0..1 << num_vars.last().unwrap())
+            .map(|_| Fr::rand(&mut rng))
+            .collect();
+        let mut fs_rng = Blake2b512Rng::setup();
+        let (outputs, proof) = GKR::prove(&mut fs_rng, &circuit, &inputs).unwrap();
+        assert_eq!(outputs, circuit.evaluate(&inputs)[0].evaluations);
+        let mut fs_rng = Blake2b512Rng::setup();
+        let subclaim = GKR::verify(&mut fs_rng, &circuit, &outputs, &proof).unwrap();
+        assert!(subclaim.verify_subclaim(&inputs));
+    }
+}
+
+fn test_input_claim_with_pcs<P: MultilinearPCS<Fr>>() {
+    let mut rng = test_rng();
+    let circuit = random_circuit(&[3, 4, 5], &mut rng);
+    let input = DenseMultilinearExtension::<Fr>::rand(5, &mut rng);
+    let pp = P::setup(5, &mut rng).unwrap();
+    let commitment = P::commit(&pp, &input).unwrap();
+
+    let mut fs_rng = Blake2b512Rng::setup();
+    let (outputs, proof) = GKR::prove(&mut fs_rng, &circuit, &input.evaluations).unwrap();
+    let mut fs_rng = Blake2b512Rng::setup();
+    let subclaim = GKR::verify(&mut fs_rng, &circuit, &outputs, &proof).unwrap();
+    let opening = P::open(&pp, &input, &subclaim.point).unwrap();
+    assert!(subclaim
+        .verify_with_pcs::<P>(&pp, &commitment, &opening)
+        .unwrap());
+}
+
+#[test]
+fn test_input_claim_with_transparent_pcs() {
+    test_input_claim_with_pcs::<TransparentPCS<Fr>>();
+}
+
+#[test]
+fn test_input_claim_with_kzg() {
+    test_input_claim_with_pcs::<MultilinearKZG<Bls12_381>>();
+}
+
+fn test_data_parallel_circuit<F: Field>(num_vars: &[usize], log_copies: usize) {
+    let mut rng = test_rng();
+    let circuit = DataParallelCircuit {
+        sub_circuit: random_circuit(num_vars, &mut rng),
+        log_copies,
+    };
+    let inputs: Vec<Vec<F>> = (0..1 << log_copies)
+        .map(|_| {
+            (0..1 << circuit.sub_circuit.input_num_vars)
+                .map(|_| F::rand(&mut rng))
+                .collect()
+        })
+        .collect();
+
+    let mut fs_rng = Blake2b512Rng::setup();
+    let (outputs, proof) =
+        DataParallelGKR::prove(&mut fs_rng, &circuit, &inputs).expect("fail to prove");
+    let output_size = 1 << circuit.sub_circuit.layers[0].num_vars;
+    for (copy_outputs, copy_inputs) in outputs.chunks(output_size).zip(&inputs) {
+        assert_eq!(
+            copy_outputs,
+            &circuit.sub_circuit.evaluate(copy_inputs)[0].evaluations[..]
+        );
+    }
+
+    let mut fs_rng = Blake2b512Rng::setup();
+    let subclaim = DataParallelGKR::verify(&mut fs_rng, &circuit, &outputs, &proof)
+        .expect("verification failed");
+    assert!(subclaim.verify_subclaim(&circuit.combine_inputs(&inputs)));
+
+    // a wrong output of a single copy is rejected
+    let mut wrong_outputs = outputs.clone();
+    wrong_outputs[outputs.len() - 1] += F::one();
+    let mut fs_rng = Blake2b512Rng::setup();
+    assert!(DataParallelGKR::verify(&mut fs_rng, &circuit, &wrong_outputs, &proof).is_err());
+}
+
+#[test]
+fn test_data_parallel() {
+    test_data_parallel_circuit::<Fr>(&[2, 3, 3], 3);
+    test_data_parallel_circuit::<Fr>(&[1, 4, 2, 5], 2);
+    test_data_parallel_circuit::<Fr>(&[3, 3], 0);
+}
+
+#[test]
+fn test_data_parallel_errors() {
+    let mut rng = test_rng();
+    let empty = DataParallelCircuit {
+        sub_circuit: LayeredCircuit {
+            input_num_vars: 1,
+            layers: Vec::new(),
+        },
+        log_copies: 1,
+    };
+    let inputs = vec![vec![Fr::from(1u64); 2]; 2];
+    let mut fs_rng = Blake2b512Rng::setup();
+    assert!(DataParallelGKR::prove(&mut fs_rng, &empty, &inputs).is_err());
+
+    let circuit = DataParallelCircuit {
+        sub_circuit: random_circuit(&[1, 2], &mut rng),
+        log_copies: 1,
+    };
+    let mut fs_rng = Blake2b512Rng::setup();
+    let (outputs, proof) = DataParallelGKR::prove(&mut fs_rng, &circuit, &inputs).unwrap();
+    let mut fs_rng = Blake2b512Rng::setup();
+    assert!(DataParallelGKR::verify(&mut fs_rng, &empty, &outputs, &proof).is_err());
+
+    // wrong number of copies, and too many inputs for a copy
+    let mut fs_rng = Blake2b512Rng::setup();
+    assert!(DataParallelGKR::prove(&mut fs_rng, &circuit, &inputs[..1]).is_err());
+    let mut fs_rng = Blake2b512Rng::setup();
+    let oversized = vec![vec![Fr::from(1u64); 5], vec![Fr::from(1u64); 2]];
+    assert!(DataParallelGKR::prove(&mut fs_rng, &circuit, &oversized).is_err());
+}
+
+#[test]
+fn test_builder() {
+    // outputs (a + b) * (c * d) + 5 and a * a, where `a` and 5 are relayed to the top layer
+    let mut builder = CircuitBuilder::new();
+    let (a, b, c, d) = (
+        builder.input(),
+        builder.input(),
+        builder.input(),
+        builder.input(),
+    );
+    let five = builder.constant(Fr::from(5u64));
+    let sum = builder.add(a, b);
+    let prod = builder.mul(c, d);
+    let prod = builder.mul(sum, prod);
+    let out = builder.add(prod, five);
+    builder.output(out);
+    let square = builder.mul(a, a);
+    builder.output(square);
+    builder.output(a);
+    let compiled = builder.build();
+    assert_eq!(compiled.circuit.layers.len(), 3);
+
+    let inputs: Vec<_> = [2u64, 3, 4, 5].iter().map(|&x| Fr::from(x)).collect();
+    let expected: Vec<_> = [105u64, 4, 2].iter().map(|&x| Fr::from(x)).collect();
+    assert_eq!(compiled.outputs(&inputs), expected);
+
+    let input_layer = compiled.input_layer(&inputs);
+    let mut fs_rng = Blake2b512Rng::setup();
+    let (outputs, proof) = GKR::prove(&mut fs_rng, &compiled.circuit, &input_layer).unwrap();
+    assert_eq!(outputs[..3], expected[..]);
+    let mut fs_rng = Blake2b512Rng::setup();
+    let subclaim = GKR::verify(&mut fs_rng, &compiled.circuit, &outputs, &proof).unwrap();
+    assert!(subclaim.verify_subclaim(&input_layer));
+}
+
+#[test]
+fn test_wiring_predicates() {
+    let mut rng = test_rng();
+    let mut builder = CircuitBuilder::new();
+    let inputs: Vec<_> = (0..6).map(|_| builder.input()).collect();
+    let mut wires = inputs.clone();
+    for i in 0..12 {
+        let (left, right) = (wires[rng.gen_range(0..wires.len())], wires[i]);
+        let wire = if rng.gen() {
+            builder.add(left, right)
+        } else {
+            builder.mul(left, right)
+        };
+        wires.push(wire);
+    }
+    for &wire in &wires[wires.len() - 4..] {
+        builder.output(wire);
+    }
+    let compiled = builder.build();
+    let values = compiled.evaluate(&(0..6).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>());
+
+    for i in 0..compiled.circuit.layers.len() {
+        let (add, mult) = compiled.circuit.wiring_predicates::<Fr>(i);
+        let (layer, below) = (&values[i], &values[i + 1]);
+        // V_i(z) = sum add(z,x,y)(V(x) + V(y)) + mult(z,x,y)V(x)V(y)
+        let mut expected = vec![Fr::from(0u64); layer.evaluations.len()];
+        for &(z, x, y, c) in &add.gates {
+            expected[z] += c * (below[x] + below[y]);
+        }
+        for &(z, x, y, c) in &mult.gates {
+            expected[z] += c * below[x] * below[y];
+        }
+        assert_eq!(expected, layer.evaluations);
+
+        // the mult predicate is the f1 of a GKR round function with f2 = f3 = V_{i+1}
+        let g: Vec<_> = (0..layer.num_vars).map(|_| Fr::rand(&mut rng)).collect();
+        let f1 = mult.to_sparse_mle();
+        let mut fs_rng = Blake2b512Rng::setup();
+        let proof = GKRRoundSumcheck::prove(&mut fs_rng, &f1, below, below, &g);
+        let mut fs_rng = Blake2b512Rng::setup();
+        let subclaim = GKRRoundSumcheck::verify(
+            &mut fs_rng,
+            below.num_vars,
+            below.num_vars,
+            &proof,
+            proof.extract_sum().unwrap(),
+        )
+        .unwrap();
+        assert!(subclaim.verify_subclaim(&f1, below, below, &g));
+    }
+}
+
+const FIXTURE: &str = "\
+# (a + b) * (c * d) + 5, where 5 is relayed by `mul 2 4 5` and `mul 1 2 3`
+inputs 3 4      # number of variables of the input layer, number of inputs
+constant 5      # constants follow the inputs in the input layer
+constant 1
+layer 0         # layers go from the output layer to the layer right above the input
+add 0 0 1       # gate type, output index, left input index, right input index
+layer 1
+mul 1 2 3
+mul 0 0 1
+
+layer 2
+add 0 0 1
+mul 1 2 3
+mul 2 4 5
+mul 3 5 5       # the constant one is relayed as well
+";
+
+#[test]
+fn test_parse_circuit() {
+    let compiled = parse_circuit::<Fr>(FIXTURE).unwrap();
+    assert_eq!(compiled.num_inputs, 4);
+    assert_eq!(compiled.constants, vec![Fr::from(5u64), Fr::from(1u64)]);
+    assert_eq!(compiled.circuit.layers.len(), 3);
+    let inputs: Vec<_> = [2u64, 3, 4, 5].iter().map(|&x| Fr::from(x)).collect();
+    assert_eq!(compiled.outputs(&inputs), vec![Fr::from(105u64)]);
+
+    // the writer outputs the parsed circuit
+    let text = write_circuit(&compiled);
+    assert_eq!(parse_circuit::<Fr>(&text).unwrap(), compiled);
+}
+
+#[test]
+fn test_write_built_circuit() {
+    let mut builder = CircuitBuilder::new();
+    let (a, b) = (builder.input(), builder.input());
+    let c = builder.constant(-Fr::from(3u64));
+    let sum = builder.add(a, c);
+    let prod = builder.mul(sum, b);
+    let prod = builder.mul(prod, a);
+    builder.output(prod);
+    builder.output(b);
+    let compiled = builder.build();
+
+    let parsed: CompiledCircuit<Fr> = write_circuit(&compiled).parse().unwrap();
+    assert_eq!(parsed, compiled);
+}
+
+#[test]
+fn test_parse_circuit_errors() {
+    let cases = [
+        "layer 0\nadd 0 0 1\n",
+        "inputs 1 2\n",
+        "inputs 1 2\nlayer 0\nsub 0 0 1\n",
+        "inputs 1 2\nlayer 0\nadd 0 0 2\n",
+        "inputs 1 2\nlayer 1\nadd 1 0 1\n",
+        "inputs 1 2\nlayer 0\nadd 0 0\n",
+        "inputs 1 2\nconstant 1\nlayer 0\nadd 0 0 1\n",
+        "inputs 1 1\nconstant x\nlayer 0\nadd 0 0 1\n",
+        "inputs 1 2\nlayer 0\nadd 0 0 1 1\n",
+        "inputs 1 2\nadd 0 0 1\n",
+    ];
+    for case in cases.iter() {
+        assert!(parse_circuit::<Fr>(case).is_err(), "{}", case);
+    }
+}
+
+#[test]
+fn test_parse_circuit_oversized() {
+    let max = usize::MAX.to_string();
+    let cases = [
+        "inputs 1 2\nlayer 100\nadd 0 0 1\n".to_string(),
+        "inputs 64 2\nlayer 0\nadd 0 0 1\n".to_string(),
+        "inputs 31 2\nlayer 0\nadd 0 0 1\n".to_string(),
+        format!("inputs {} 2\nlayer 0\nadd 0 0 1\n", max),
+        format!("inputs 1 {}\nconstant 1\nlayer 0\nadd 0 0 1\n", max),
+        format!("inputs 1 2\nlayer {}\nadd 0 0 1\n", max),
+    ];
+    for case in cases.iter() {
+        assert!(parse_circuit::<Fr>(case).is_err(), "{}", case);
+    }
+}
@@ -0,0 +1,176 @@
+//! Reduction of several evaluation claims on the same multilinear extension to a single claim.
+//!
+//! After a GKR round, the verifier holds claims `V(u) = a` and `V(v) = b` on the layer below. Two
+//! techniques are provided to merge them:
+//! * `combine_eq_tables`: the random linear combination `alpha * eq(u, ·) + beta * eq(v, ·)` of
+//!   [XZZPS19](https://eprint.iacr.org/2019/317.pdf#subsection.3.5), used as the weights over g of
+//!   the next round sumcheck.
+//! * `ClaimReduction`: restriction of `V` to the curve passing through all the points, as in
+//!   [Tha13](https://arxiv.org/abs/1304.3812) (Section 3). It outputs a single claim `V(r) = c`.
+
+use crate::ml_sumcheck::protocol::verifier::interpolate_uni_poly;
+use crate::rng::FeedableRNG;
+use crate::utils::eq_evaluations;
+use ark_ff::Field;
+use ark_poly::{DenseMultilinearExtension, Polynomial};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::marker::PhantomData;
+use ark_std::vec::Vec;
+
+/// Returns the table of `sum_i coefficients[i] * eq(points[i], ·)` over the boolean hypercube.
+///
+/// Returns an error if there are no points, if the number of coefficients does not match, or if
+/// the points have different numbers of variables.
+pub fn combine_eq_tables<F: Field>(
+    points: &[Vec<F>],
+    coefficients: &[F],
+) -> Result<Vec<F>, crate::Error> {
+    let num_vars = check_points(points, None)?;
+    if coefficients.len() != points.len() {
+        return Err(crate::Error::OtherError(
+            "Number of coefficients does not match the number of points.".into(),
+        ));
+    }
+    let mut table = vec![F::zero(); 1 << num_vars];
+    for (point, coefficient) in points.iter().zip(coefficients) {
+        for (t, e) in table.iter_mut().zip(eq_evaluations(point)) {
+            *t += *coefficient * e;
+        }
+    }
+    Ok(table)
+}
+
+/// Checks that `points` is not empty and that all points have the same number of variables, which
+/// must be `num_vars` if given. Returns the number of variables of the points.
+fn check_points<F: Field>(
+    points: &[Vec<F>],
+    num_vars: Option<usize>,
+) -> Result<usize, crate::Error> {
+    let first = points
+        .first()
+        .ok_or_else(|| crate::Error::OtherError("No points to combine.".into()))?;
+    let num_vars = num_vars.unwrap_or(first.len());
+    if points.iter().any(|p| p.len() != num_vars) {
+        return Err(crate::Error::OtherError(
+            "Points have a wrong number of variables.".into(),
+        ));
+    }
+    Ok(num_vars)
+}
+
+/// Message sent by the prover to reduce k claims to one.
+///
+/// The prover sends `q(t) = V(gamma(t))` where `gamma` is the curve of degree `k - 1` with
+/// `gamma(i) = points[i]`. As `q(0), ..., q(k - 1)` are the claimed values, only
+/// `q(k), ..., q((k - 1) * num_vars)` are sent.
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ClaimReductionProof<F: Field> {
+    /// evaluations of q at k, k + 1, ..., (k - 1) * num_vars
+    pub(crate) evaluations: Vec<F>,
+}
+
+/// Reduced claim: the multilinear extension evaluated at `point` is `expected_evaluation`.
+pub struct ReducedClaim<F: Field> {
+    /// the point that the multilinear extension is evaluated to
+    pub point: Vec<F>,
+    /// the expected evaluation
+    pub expected_evaluation: F,
+}
+
+/// Reduction of k evaluation claims on a multilinear extension to one claim by line restriction
+pub struct ClaimReduction<F: Field> {
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> ClaimReduction<F> {
+    /// Returns `gamma(t)`, the point of the curve of degree `k - 1` passing through `points` at
+    /// t = 0, ..., k - 1.
+    fn curve_at(points: &[Vec<F>], t: F) -> Vec<F> {
+        (0..points[0].len())
+            .map(|j| {
+                let coordinates: Vec<_> = points.iter().map(|p| p[j]).collect();
+                interpolate_uni_poly(&coordinates, t)
+            })
+            .collect()
+    }
+
+    /// Degree of `q(t) = V(gamma(t))`.
+    fn degree(num_points: usize, num_vars: usize) -> usize {
+        (num_points - 1) * num_vars
+    }
+
+    /// Reduces the claims of `poly` at `points` to a single claim, and returns the proof along with
+    /// the reduced claim.
+    ///
+    /// Returns an error if there are no points or if a point does not have the number of variables
+    /// of `poly`.
+    pub fn prove(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        poly: &DenseMultilinearExtension<F>,
+        points: &[Vec<F>],
+    ) -> Result<(ClaimReductionProof<F>, ReducedClaim<F>), crate::Error> {
+        check_points(points, Some(poly.num_vars))?;
+        let values: Vec<_> = points.iter().map(|p| poly.evaluate(p)).collect();
+        let degree = Self::degree(points.len(), poly.num_vars);
+        let evaluations: Vec<_> = (points.len()..=degree)
+            .map(|t| poly.evaluate(&Self::curve_at(points, F::from(t as u64))))
+            .collect();
+        let proof = ClaimReductionProof { evaluations };
+
+        fs_rng.feed(&values)?;
+        fs_rng.feed(&proof)?;
+        let r = F::rand(fs_rng);
+        let point = Self::curve_at(points, r);
+        let expected_evaluation = poly.evaluate(&point);
+        Ok((
+            proof,
+            ReducedClaim {
+                point,
+                expected_evaluation,
+            },
+        ))
+    }
+
+    /// Verifies the reduction of the claims `V(points[i]) = values[i]`, and returns the reduced
+    /// claim.
+    ///
+    /// If all claims are true, then the reduced claim is true. Otherwise, it is very likely that the
+    /// reduced claim is false.
+    pub fn verify(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        points: &[Vec<F>],
+        values: &[F],
+        proof: &ClaimReductionProof<F>,
+    ) -> Result<ReducedClaim<F>, crate::Error> {
+        if points.is_empty() || points.len() != values.len() {
+            return Err(crate::Error::Reject(Some(
+                "Number of points does not match the number of claimed values.".into(),
+            )));
+        }
+        let num_vars = points[0].len();
+        if points.iter().any(|p| p.len() != num_vars) {
+            return Err(crate::Error::Reject(Some(
+                "Points have different number of variables.".into(),
+            )));
+        }
+        let degree = Self::degree(points.len(), num_vars);
+        if values.len() + proof.evaluations.len() != ark_std::cmp::max(degree + 1, values.len()) {
+            return Err(crate::Error::Reject(Some(
+                "Incorrect number of evaluations.".into(),
+            )));
+        }
+
+        fs_rng.feed(&values.to_vec())?;
+        fs_rng.feed(proof)?;
+        let r = F::rand(fs_rng);
+        let q: Vec<_> = values
+            .iter()
+            .chain(proof.evaluations.iter())
+            .copied()
+            .collect();
+        Ok(ReducedClaim {
+            point: Self::curve_at(points, r),
+            expected_evaluation: interpolate_uni_poly(&q, r),
+        })
+    }
+}
@@ -1,24 +1,158 @@
 //! Data structures used by GKR Round Sumcheck
 
-use crate::ml_sumcheck::protocol::prover::ProverMsg;
+use crate::gkr_round_sumcheck::wiring::WiringPredicate;
+use crate::ml_sumcheck::protocol::prover::{CompressedProverMsg, ProverMsg};
 use ark_ff::Field;
 use ark_poly::{DenseMultilinearExtension, Polynomial, SparseMultilinearExtension};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::vec::Vec;
 
+/// Gate-list representation of the wiring predicate `f1` of a GKR round function.
+///
+/// Each entry `(z, x, y, c)` states that `f1(z, x, y) = c` on the boolean hypercube, where `z`, `x`
+/// and `y` are little endian indices of `dim_g`, `dim_x` and `dim_y` bits. All other entries of `f1`
+/// are zero.
+/// Unlike `SparseMultilinearExtension`, this representation allows phase one and phase two to be
+/// initialized in time linear to the number of gates, following Algorithm 1 and 4 of
+/// [XZZPS19](https://eprint.iacr.org/2019/317.pdf#subsection.3.4).
+#[derive(Clone, Debug, PartialEq)]
+pub struct GateList<F: Field> {
+    /// number of variables of g
+    pub dim_g: usize,
+    /// number of variables of x
+    pub dim_x: usize,
+    /// number of variables of y
+    pub dim_y: usize,
+    /// list of non-zero entries `(z, x, y, value)`
+    pub gates: Vec<(usize, usize, usize, F)>,
+}
+
+impl<F: Field> GateList<F> {
+    /// Returns an empty wiring predicate in `dim_g + dim_x + dim_y` variables.
+    pub fn new(dim_g: usize, dim_x: usize, dim_y: usize) -> Self {
+        Self {
+            dim_g,
+            dim_x,
+            dim_y,
+            gates: Vec::new(),
+        }
+    }
+
+    /// Add `value` to the predicate at `(z, x, y)`.
+    pub fn add_gate(&mut self, z: usize, x: usize, y: usize, value: F) {
+        assert!(
+            z < 1 << self.dim_g && x < 1 << self.dim_x && y < 1 << self.dim_y,
+            "index out of range"
+        );
+        self.gates.push((z, x, y, value));
+    }
+
+    /// Builds the gate list from the non-zero entries of a sparse wiring predicate in
+    /// `dim_g + dim_x + dim_y` variables.
+    pub fn from_sparse_mle(
+        f1: &SparseMultilinearExtension<F>,
+        dim_g: usize,
+        dim_x: usize,
+        dim_y: usize,
+    ) -> Self {
+        assert_eq!(f1.num_vars, dim_g + dim_x + dim_y);
+        let gates = f1
+            .evaluations
+            .iter()
+            .filter(|(_, v)| !v.is_zero())
+            .map(|(&zxy, &v)| {
+                let z = zxy & ((1 << dim_g) - 1);
+                let x = (zxy >> dim_g) & ((1 << dim_x) - 1);
+                let y = zxy >> (dim_g + dim_x);
+                (z, x, y, v)
+            })
+            .collect();
+        Self {
+            dim_g,
+            dim_x,
+            dim_y,
+            gates,
+        }
+    }
+
+    /// Converts the gate list into a sparse multilinear extension in `dim_g + dim_x + dim_y`
+    /// variables.
+    pub fn to_sparse_mle(&self) -> SparseMultilinearExtension<F> {
+        let mut evaluations = ark_std::collections::BTreeMap::new();
+        for &(z, x, y, v) in &self.gates {
+            *evaluations
+                .entry(z + (x << self.dim_g) + (y << (self.dim_g + self.dim_x)))
+                .or_insert_with(F::zero) += v;
+        }
+        let evaluations: Vec<_> = evaluations.into_iter().collect();
+        SparseMultilinearExtension::from_evaluations(
+            self.dim_g + self.dim_x + self.dim_y,
+            &evaluations,
+        )
+    }
+}
+
 /// Proof for GKR Round Function
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
 pub struct GKRProof<F: Field> {
     pub(crate) phase1_sumcheck_msgs: Vec<ProverMsg<F>>,
     pub(crate) phase2_sumcheck_msgs: Vec<ProverMsg<F>>,
 }
 
 impl<F: Field> GKRProof<F> {
+    /// Returns the prover messages of the sumcheck over x.
+    pub fn phase1_sumcheck_msgs(&self) -> &[ProverMsg<F>] {
+        &self.phase1_sumcheck_msgs
+    }
+
+    /// Returns the prover messages of the sumcheck over y.
+    pub fn phase2_sumcheck_msgs(&self) -> &[ProverMsg<F>] {
+        &self.phase2_sumcheck_msgs
+    }
+
     /// Extract the witness (i.e. the sum of GKR)
-    pub fn extract_sum(&self) -> F {
-        self.phase1_sumcheck_msgs[0].evaluations[0] + self.phase1_sumcheck_msgs[0].evaluations[1]
+    ///
+    /// If x has no variables, the claim of phase two is the sum and it is read from the first
+    /// message of phase two. Returns `None` if the proof has no messages to read the sum from.
+    pub fn extract_sum(&self) -> Option<F> {
+        let msg = self
+            .phase1_sumcheck_msgs
+            .first()
+            .or_else(|| self.phase2_sumcheck_msgs.first())?;
+        match msg.evaluations[..] {
+            [p0, p1, ..] => Some(p0 + p1),
+            _ => None,
+        }
+    }
+}
+
+/// Proof for GKR Round Function without `P(1)` in each round
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct CompressedGKRProof<F: Field> {
+    pub(crate) phase1_sumcheck_msgs: Vec<CompressedProverMsg<F>>,
+    pub(crate) phase2_sumcheck_msgs: Vec<CompressedProverMsg<F>>,
+}
+
+impl<F: Field> GKRProof<F> {
+    /// Compresses the proof by dropping `P(1)` from each round.
+    pub fn compress(&self) -> CompressedGKRProof<F> {
+        CompressedGKRProof {
+            phase1_sumcheck_msgs: self
+                .phase1_sumcheck_msgs
+                .iter()
+                .map(|msg| msg.compress())
+                .collect(),
+            phase2_sumcheck_msgs: self
+                .phase2_sumcheck_msgs
+                .iter()
+                .map(|msg| msg.compress())
+                .collect(),
+        }
     }
 }
 
 /// Subclaim for GKR Round Function
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
 pub struct GKRRoundSumcheckSubClaim<F: Field> {
     /// u
     pub u: Vec<F>,
@@ -30,27 +164,22 @@ pub struct GKRRoundSumcheckSubClaim<F: Field> {
 
 impl<F: Field> GKRRoundSumcheckSubClaim<F> {
     /// Verify that the subclaim is true by evaluating the GKR Round function.
+    ///
+    /// `f1` may be the sparse multilinear extension of the wiring predicate, or any
+    /// `WiringPredicate` that evaluates it faster.
     pub fn verify_subclaim(
         &self,
-        f1: &SparseMultilinearExtension<F>,
+        f1: &impl WiringPredicate<F>,
         f2: &DenseMultilinearExtension<F>,
         f3: &DenseMultilinearExtension<F>,
         g: &[F],
     ) -> bool {
-        let dim = self.u.len();
-        assert_eq!(self.v.len(), dim);
-        assert_eq!(f1.num_vars, 3 * dim);
-        assert_eq!(f2.num_vars, dim);
-        assert_eq!(f3.num_vars, dim);
-        assert_eq!(g.len(), dim);
-
-        let guv: Vec<_> = g
-            .iter()
-            .chain(self.u.iter())
-            .chain(self.v.iter())
-            .copied()
-            .collect();
-        let actual_evaluation = f1.evaluate(&guv) * f2.evaluate(&self.u) * f3.evaluate(&self.v);
+        assert_eq!(f2.num_vars, self.u.len());
+        assert_eq!(f3.num_vars, self.v.len());
+        assert_eq!(f1.num_vars(), g.len() + self.u.len() + self.v.len());
+
+        let actual_evaluation =
+            f1.evaluate_wiring(g, &self.u, &self.v) * f2.evaluate(&self.u) * f3.evaluate(&self.v);
 
         actual_evaluation == self.expected_evaluation
     }
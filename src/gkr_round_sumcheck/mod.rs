@@ -2,54 +2,95 @@
 //!
 //! GKR Round Sumcheck will use `ml_sumcheck` as a subroutine.
 
+pub mod claim_reduction;
 pub mod data_structures;
 #[cfg(test)]
 mod test;
+pub mod wiring;
+pub mod zk;
 
-use crate::gkr_round_sumcheck::data_structures::{GKRProof, GKRRoundSumcheckSubClaim};
-use crate::ml_sumcheck::protocol::prover::ProverState;
+use crate::gkr_round_sumcheck::data_structures::{
+    CompressedGKRProof, GKRProof, GKRRoundSumcheckSubClaim, GateList,
+};
+use crate::ml_sumcheck::protocol::prover::{CompressedProverMsg, ProverMsg, ProverState};
+use crate::ml_sumcheck::protocol::verifier::SubClaim;
 use crate::ml_sumcheck::protocol::{IPForMLSumcheck, ListOfProductsOfPolynomials, PolynomialInfo};
 use crate::rng::FeedableRNG;
+use crate::utils::eq_evaluations;
 use ark_ff::{Field, Zero};
 use ark_poly::{
     DenseMultilinearExtension, MultilinearExtension, Polynomial, SparseMultilinearExtension,
 };
 use ark_std::marker::PhantomData;
-use ark_std::rc::Rc;
+use ark_std::sync::Arc;
 use ark_std::vec::Vec;
 
 /// Takes multilinear f1, f3, and input g = g1,...,gl. Returns h_g, and f1 fixed at g.
+///
+/// f1 has `g.len() + dim_x + f3.num_vars` variables, where `dim_x` is the number of variables of x.
 pub fn initialize_phase_one<F: Field>(
     f1: &SparseMultilinearExtension<F>,
     f3: &DenseMultilinearExtension<F>,
     g: &[F],
 ) -> (DenseMultilinearExtension<F>, SparseMultilinearExtension<F>) {
-    let dim = f3.num_vars; // 'l` in paper
-    assert_eq!(f1.num_vars, dim * 3);
-    assert_eq!(g.len(), dim);
-    let mut a_hg: Vec<_> = (0..(1 << dim)).map(|_| F::zero()).collect();
+    let dim_y = f3.num_vars;
+    assert!(f1.num_vars >= g.len() + dim_y);
+    let dim_x = f1.num_vars - g.len() - dim_y;
+    let mut a_hg: Vec<_> = (0..(1 << dim_x)).map(|_| F::zero()).collect();
     let f1_at_g = f1.fix_variables(g);
     for (xy, v) in f1_at_g.evaluations.iter() {
         if v != &F::zero() {
-            let x = xy & ((1 << dim) - 1);
-            let y = xy >> dim;
+            let x = xy & ((1 << dim_x) - 1);
+            let y = xy >> dim_x;
             a_hg[x] += *v * f3[y];
         }
     }
 
-    let hg = DenseMultilinearExtension::from_evaluations_vec(dim, a_hg);
+    let hg = DenseMultilinearExtension::from_evaluations_vec(dim_x, a_hg);
     (hg, f1_at_g)
 }
 
+/// Takes the gate list of f1, f3, and input g = g1,...,gl. Returns h_g, and the table of eq(g, ·).
+///
+/// This is Algorithm 1 of [XZZPS19](https://eprint.iacr.org/2019/317.pdf#subsection.3.4): the
+/// table of eq(g, ·) is computed once and the gate list is scanned, so it runs in O(2^l + |gates|).
+pub fn initialize_phase_one_with_gates<F: Field>(
+    f1: &GateList<F>,
+    f3: &DenseMultilinearExtension<F>,
+    g: &[F],
+) -> (DenseMultilinearExtension<F>, Vec<F>) {
+    assert_eq!(f1.dim_g, g.len());
+    let g_table = eq_evaluations(g);
+    let hg = initialize_phase_one_with_table(f1, f3, &g_table);
+    (hg, g_table)
+}
+
+/// Same as `initialize_phase_one_with_gates`, but takes the table of the weights over g instead of
+/// g, e.g. a random linear combination of eq(g1, ·) and eq(g2, ·) when two claims are combined.
+/// Returns h_g.
+pub fn initialize_phase_one_with_table<F: Field>(
+    f1: &GateList<F>,
+    f3: &DenseMultilinearExtension<F>,
+    g_table: &[F],
+) -> DenseMultilinearExtension<F> {
+    assert_eq!(g_table.len(), 1 << f1.dim_g);
+    assert_eq!(f1.dim_y, f3.num_vars);
+    let mut a_hg = vec![F::zero(); 1 << f1.dim_x];
+    for &(z, x, y, c) in &f1.gates {
+        a_hg[x] += g_table[z] * c * f3[y];
+    }
+    DenseMultilinearExtension::from_evaluations_vec(f1.dim_x, a_hg)
+}
+
 /// Takes h_g and returns a sumcheck state
 pub fn start_phase1_sumcheck<F: Field>(
     h_g: &DenseMultilinearExtension<F>,
     f2: &DenseMultilinearExtension<F>,
 ) -> ProverState<F> {
-    let dim = h_g.num_vars;
-    assert_eq!(f2.num_vars, dim);
-    let mut poly = ListOfProductsOfPolynomials::new(dim);
-    poly.add_product(vec![Rc::new(h_g.clone()), Rc::new(f2.clone())], F::one());
+    let dim_x = h_g.num_vars;
+    assert_eq!(f2.num_vars, dim_x);
+    let mut poly = ListOfProductsOfPolynomials::new(dim_x);
+    poly.add_product(vec![Arc::new(h_g.clone()), Arc::new(f2.clone())], F::one());
     IPForMLSumcheck::prover_init(&poly)
 }
 
@@ -58,10 +99,29 @@ pub fn initialize_phase_two<F: Field>(
     f1_g: &SparseMultilinearExtension<F>,
     u: &[F],
 ) -> DenseMultilinearExtension<F> {
-    assert_eq!(u.len() * 2, f1_g.num_vars);
+    assert!(u.len() <= f1_g.num_vars);
     f1_g.fix_variables(u).to_dense_multilinear_extension()
 }
 
+/// Takes the gate list of f1, the table of eq(g, ·), phase one randomness u. Returns f1 fixed at g||u
+///
+/// This is Algorithm 4 of [XZZPS19](https://eprint.iacr.org/2019/317.pdf#subsection.3.4), which runs
+/// in O(2^l + |gates|).
+pub fn initialize_phase_two_with_gates<F: Field>(
+    f1: &GateList<F>,
+    g_table: &[F],
+    u: &[F],
+) -> DenseMultilinearExtension<F> {
+    assert_eq!(u.len(), f1.dim_x);
+    assert_eq!(g_table.len(), 1 << f1.dim_g);
+    let u_table = eq_evaluations(u);
+    let mut a_f1_gu = vec![F::zero(); 1 << f1.dim_y];
+    for &(z, x, y, c) in &f1.gates {
+        a_f1_gu[y] += g_table[z] * u_table[x] * c;
+    }
+    DenseMultilinearExtension::from_evaluations_vec(f1.dim_y, a_f1_gu)
+}
+
 /// Takes f1 fixed at g||u, f3, and f2 evaluated at u.
 pub fn start_phase2_sumcheck<F: Field>(
     f1_gu: &DenseMultilinearExtension<F>,
@@ -74,10 +134,28 @@ pub fn start_phase2_sumcheck<F: Field>(
         zero
     };
 
-    let dim = f1_gu.num_vars;
-    assert_eq!(f3.num_vars, dim);
-    let mut poly = ListOfProductsOfPolynomials::new(dim);
-    poly.add_product(vec![Rc::new(f1_gu.clone()), Rc::new(f3_f2u)], F::one());
+    let dim_y = f1_gu.num_vars;
+    assert_eq!(f3.num_vars, dim_y);
+    let mut poly = ListOfProductsOfPolynomials::new(dim_y);
+    poly.add_product(vec![Arc::new(f1_gu.clone()), Arc::new(f3_f2u)], F::one());
+    IPForMLSumcheck::prover_init(&poly)
+}
+
+/// Same as `start_phase2_sumcheck`, but keeps f1 fixed at g||u sparse, so that the prover never
+/// builds its table of size 2^dim_y.
+pub fn start_phase2_sumcheck_sparse<F: Field>(
+    f1_gu: &SparseMultilinearExtension<F>,
+    f3: &DenseMultilinearExtension<F>,
+    f2_u: F,
+) -> ProverState<F> {
+    let dim_y = f1_gu.num_vars;
+    assert_eq!(f3.num_vars, dim_y);
+    let mut poly = ListOfProductsOfPolynomials::new(dim_y);
+    poly.add_product_with_sparse(
+        vec![Arc::new(f1_gu.clone())],
+        vec![Arc::new(f3.clone())],
+        f2_u,
+    );
     IPForMLSumcheck::prover_init(&poly)
 }
 
@@ -90,6 +168,9 @@ impl<F: Field> GKRRoundSumcheck<F> {
     /// Takes a GKR Round Function and input, prove the sum.
     /// * `f1`,`f2`,`f3`: represents the GKR round function
     /// * `g`: represents the fixed input.
+    ///
+    /// g, x and y may have different number of variables: f2 has `dim_x` variables, f3 has `dim_y`
+    /// variables, and f1 has `g.len() + dim_x + dim_y` variables.
     pub fn prove<R: FeedableRNG>(
         rng: &mut R,
         f1: &SparseMultilinearExtension<F>,
@@ -97,45 +178,79 @@ impl<F: Field> GKRRoundSumcheck<F> {
         f3: &DenseMultilinearExtension<F>,
         g: &[F],
     ) -> GKRProof<F> {
-        assert_eq!(f1.num_vars, 3 * f2.num_vars);
-        assert_eq!(f1.num_vars, 3 * f3.num_vars);
+        assert_eq!(f1.num_vars, g.len() + f2.num_vars + f3.num_vars);
 
-        let dim = f2.num_vars;
-        let g = g.to_vec();
+        let (h_g, f1_g) = initialize_phase_one(f1, f3, g);
+        Self::prove_phases(rng, &h_g, f2, f3.num_vars, |u, f2_u| {
+            start_phase2_sumcheck_sparse(&f1_g.fix_variables(u), f3, f2_u)
+        })
+    }
 
-        let (h_g, f1_g) = initialize_phase_one(f1, f3, &g);
-        let mut phase1_ps = start_phase1_sumcheck(&h_g, f2);
-        let mut phase1_vm = None;
-        let mut phase1_prover_msgs = Vec::with_capacity(dim);
-        let mut u = Vec::with_capacity(dim);
-        for _ in 0..dim {
-            let pm = IPForMLSumcheck::prove_round(&mut phase1_ps, &phase1_vm);
+    /// Same as `prove`, but takes the wiring predicate as a gate list and initializes both phases in
+    /// time linear to the number of gates.
+    /// * `f1`: gate list of the wiring predicate
+    /// * `f2`,`f3`: represents the GKR round function
+    /// * `g`: represents the fixed input.
+    pub fn prove_with_gates<R: FeedableRNG>(
+        rng: &mut R,
+        f1: &GateList<F>,
+        f2: &DenseMultilinearExtension<F>,
+        f3: &DenseMultilinearExtension<F>,
+        g: &[F],
+    ) -> GKRProof<F> {
+        assert_eq!(f1.dim_g, g.len());
+        assert_eq!(f1.dim_x, f2.num_vars);
+        assert_eq!(f1.dim_y, f3.num_vars);
 
-            rng.feed(&pm).unwrap();
-            phase1_prover_msgs.push(pm);
-            let vm = IPForMLSumcheck::sample_round(rng);
-            phase1_vm = Some(vm.clone());
-            u.push(vm.randomness);
+        let (h_g, g_table) = initialize_phase_one_with_gates(f1, f3, g);
+        Self::prove_phases(rng, &h_g, f2, f3.num_vars, |u, f2_u| {
+            start_phase2_sumcheck(&initialize_phase_two_with_gates(f1, &g_table, u), f3, f2_u)
+        })
+    }
+
+    /// Runs both sumcheck phases given h_g, where `phase_two` takes u and f2(u), and starts the
+    /// sumcheck of phase two over `dim_y` variables. A phase over zero variables has no message.
+    fn prove_phases<R: FeedableRNG>(
+        rng: &mut R,
+        h_g: &DenseMultilinearExtension<F>,
+        f2: &DenseMultilinearExtension<F>,
+        dim_y: usize,
+        phase_two: impl FnOnce(&[F], F) -> ProverState<F>,
+    ) -> GKRProof<F> {
+        let (phase1_sumcheck_msgs, u) =
+            Self::prove_phase(rng, f2.num_vars, || start_phase1_sumcheck(h_g, f2));
+        let f2_u = f2.evaluate(&u);
+        let (phase2_sumcheck_msgs, _) = Self::prove_phase(rng, dim_y, || phase_two(&u, f2_u));
+        GKRProof {
+            phase1_sumcheck_msgs,
+            phase2_sumcheck_msgs,
         }
+    }
 
-        let f1_gu = initialize_phase_two(&f1_g, &u);
-        let mut phase2_ps = start_phase2_sumcheck(&f1_gu, f3, f2.evaluate(&u));
-        let mut phase2_vm = None;
-        let mut phase2_prover_msgs = Vec::with_capacity(dim);
-        let mut v = Vec::with_capacity(dim);
-        for _ in 0..dim {
-            let pm = IPForMLSumcheck::prove_round(&mut phase2_ps, &phase2_vm);
+    /// Runs the sumcheck of a phase over `num_vars` variables from the prover state returned by
+    /// `start`, feeding each message to the transcript. Returns the messages and the randomness of
+    /// the phase. A phase over zero variables is not started, and has no message.
+    pub(crate) fn prove_phase<R: FeedableRNG>(
+        rng: &mut R,
+        num_vars: usize,
+        start: impl FnOnce() -> ProverState<F>,
+    ) -> (Vec<ProverMsg<F>>, Vec<F>) {
+        if num_vars == 0 {
+            return (Vec::new(), Vec::new());
+        }
+        let mut prover_state = start();
+        let mut verifier_msg = None;
+        let mut prover_msgs = Vec::with_capacity(num_vars);
+        let mut randomness = Vec::with_capacity(num_vars);
+        for _ in 0..num_vars {
+            let pm = IPForMLSumcheck::prove_round(&mut prover_state, &verifier_msg);
             rng.feed(&pm).unwrap();
-            phase2_prover_msgs.push(pm);
+            prover_msgs.push(pm);
             let vm = IPForMLSumcheck::sample_round(rng);
-            phase2_vm = Some(vm.clone());
-            v.push(vm.randomness);
-        }
-
-        GKRProof {
-            phase1_sumcheck_msgs: phase1_prover_msgs,
-            phase2_sumcheck_msgs: phase2_prover_msgs,
+            randomness.push(vm.randomness);
+            verifier_msg = Some(vm);
         }
+        (prover_msgs, randomness)
     }
 
     /// Takes a GKR Round Function, input, and proof, and returns a subclaim.
@@ -143,40 +258,30 @@ impl<F: Field> GKRRoundSumcheck<F> {
     /// If the `claimed_sum` is correct, then it is `subclaim.verify_subclaim` will return true.
     /// Otherwise, it is very likely that `subclaim.verify_subclaim` will return false.
     /// Larger field size guarantees smaller soundness error.
-    /// * `f2_num_vars`: represents number of variables of f2
-    pub fn verify<R: FeedableRNG>(
+    /// * `dim_x`: represents number of variables of f2
+    /// * `dim_y`: represents number of variables of f3
+    ///
+    /// Returns an error if the proof is malformed or if the transcript fails.
+    pub fn verify<R: FeedableRNG<Error = crate::Error>>(
         rng: &mut R,
-        f2_num_vars: usize,
+        dim_x: usize,
+        dim_y: usize,
         proof: &GKRProof<F>,
         claimed_sum: F,
     ) -> Result<GKRRoundSumcheckSubClaim<F>, crate::Error> {
-        // verify first sumcheck
-        let dim = f2_num_vars;
+        Self::check_phase_shape("Phase one", dim_x, 2, &proof.phase1_sumcheck_msgs)?;
+        Self::check_phase_shape("Phase two", dim_y, 2, &proof.phase2_sumcheck_msgs)?;
 
-        let mut phase1_vs = IPForMLSumcheck::verifier_init(&PolynomialInfo {
-            max_multiplicands: 2,
-            num_variables: dim,
-        });
-
-        for i in 0..dim {
-            let pm = &proof.phase1_sumcheck_msgs[i];
-            rng.feed(pm).unwrap();
-            let _result = IPForMLSumcheck::verify_round((*pm).clone(), &mut phase1_vs, rng);
-        }
-        let phase1_subclaim = IPForMLSumcheck::check_and_generate_subclaim(phase1_vs, claimed_sum)?;
+        // verify first sumcheck
+        let phase1_subclaim =
+            Self::verify_phase(rng, dim_x, 2, &proof.phase1_sumcheck_msgs, claimed_sum)?;
         let u = phase1_subclaim.point;
 
-        let mut phase2_vs = IPForMLSumcheck::verifier_init(&PolynomialInfo {
-            max_multiplicands: 2,
-            num_variables: dim,
-        });
-        for i in 0..dim {
-            let pm = &proof.phase2_sumcheck_msgs[i];
-            rng.feed(pm).unwrap();
-            let _result = IPForMLSumcheck::verify_round((*pm).clone(), &mut phase2_vs, rng);
-        }
-        let phase2_subclaim = IPForMLSumcheck::check_and_generate_subclaim(
-            phase2_vs,
+        let phase2_subclaim = Self::verify_phase(
+            rng,
+            dim_y,
+            2,
+            &proof.phase2_sumcheck_msgs,
             phase1_subclaim.expected_evaluation,
         )?;
 
@@ -190,4 +295,129 @@ impl<F: Field> GKRRoundSumcheck<F> {
             expected_evaluation,
         })
     }
+
+    /// Verifies a proof compressed with `GKRProof::compress`. The subclaim is the same as the
+    /// one `verify` outputs for the uncompressed proof.
+    pub fn verify_compressed<R: FeedableRNG<Error = crate::Error>>(
+        rng: &mut R,
+        dim_x: usize,
+        dim_y: usize,
+        proof: &CompressedGKRProof<F>,
+        claimed_sum: F,
+    ) -> Result<GKRRoundSumcheckSubClaim<F>, crate::Error> {
+        let phase1_subclaim = Self::verify_compressed_phase(
+            rng,
+            "Phase one",
+            dim_x,
+            &proof.phase1_sumcheck_msgs,
+            claimed_sum,
+        )?;
+        let phase2_subclaim = Self::verify_compressed_phase(
+            rng,
+            "Phase two",
+            dim_y,
+            &proof.phase2_sumcheck_msgs,
+            phase1_subclaim.expected_evaluation,
+        )?;
+
+        Ok(GKRRoundSumcheckSubClaim {
+            u: phase1_subclaim.point,
+            v: phase2_subclaim.point,
+            expected_evaluation: phase2_subclaim.expected_evaluation,
+        })
+    }
+
+    /// Verifies the compressed sumcheck of a phase of degree 2, recovering each message from the
+    /// running claim before feeding it to the transcript.
+    fn verify_compressed_phase<R: FeedableRNG<Error = crate::Error>>(
+        rng: &mut R,
+        phase: &str,
+        num_vars: usize,
+        msgs: &[CompressedProverMsg<F>],
+        claimed_sum: F,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        if msgs.len() != num_vars {
+            return Err(crate::Error::Reject(Some(format!(
+                "{} proof has {} rounds, expected {}.",
+                phase,
+                msgs.len(),
+                num_vars
+            ))));
+        }
+        if num_vars == 0 {
+            return Ok(SubClaim {
+                point: Vec::new(),
+                expected_evaluation: claimed_sum,
+            });
+        }
+        let info = PolynomialInfo {
+            max_multiplicands: 2,
+            num_variables: num_vars,
+        };
+        let mut vs = IPForMLSumcheck::verifier_init_with_claim(&info, claimed_sum);
+        for compressed_msg in msgs {
+            let pm = IPForMLSumcheck::decompress_round(compressed_msg, &vs)?;
+            rng.feed(&pm)?;
+            IPForMLSumcheck::verify_round_with_claim(pm, &mut vs, rng)?;
+        }
+        IPForMLSumcheck::generate_subclaim(vs)
+    }
+
+    /// Checks that a phase has one message per variable, and that each message holds the
+    /// evaluations of a polynomial of degree `degree`.
+    pub(crate) fn check_phase_shape(
+        phase: &str,
+        num_vars: usize,
+        degree: usize,
+        msgs: &[ProverMsg<F>],
+    ) -> Result<(), crate::Error> {
+        if msgs.len() != num_vars {
+            return Err(crate::Error::Reject(Some(format!(
+                "{} proof has {} rounds, expected {}.",
+                phase,
+                msgs.len(),
+                num_vars
+            ))));
+        }
+        if let Some(round) = msgs
+            .iter()
+            .position(|msg| msg.evaluations.len() != degree + 1)
+        {
+            return Err(crate::Error::Reject(Some(format!(
+                "{} message of round {} has {} evaluations, expected {}.",
+                phase,
+                round,
+                msgs[round].evaluations.len(),
+                degree + 1
+            ))));
+        }
+        Ok(())
+    }
+
+    /// Verifies the sumcheck of a phase whose messages have been checked by `check_phase_shape`.
+    /// Each round is checked on receipt, so a bad round is rejected where it fails.
+    pub(crate) fn verify_phase<R: FeedableRNG<Error = crate::Error>>(
+        rng: &mut R,
+        num_vars: usize,
+        degree: usize,
+        msgs: &[ProverMsg<F>],
+        claimed_sum: F,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        if num_vars == 0 {
+            return Ok(SubClaim {
+                point: Vec::new(),
+                expected_evaluation: claimed_sum,
+            });
+        }
+        let info = PolynomialInfo {
+            max_multiplicands: degree,
+            num_variables: num_vars,
+        };
+        let mut vs = IPForMLSumcheck::verifier_init_with_claim(&info, claimed_sum);
+        for pm in msgs {
+            rng.feed(pm)?;
+            IPForMLSumcheck::verify_round_with_claim(pm.clone(), &mut vs, rng)?;
+        }
+        IPForMLSumcheck::generate_subclaim(vs)
+    }
 }
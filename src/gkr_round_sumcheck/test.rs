@@ -1,11 +1,25 @@
-use crate::gkr_round_sumcheck::GKRRoundSumcheck;
+use crate::gkr_round_sumcheck::claim_reduction::{combine_eq_tables, ClaimReduction};
+use crate::gkr_round_sumcheck::data_structures::{
+    CompressedGKRProof, GKRProof, GKRRoundSumcheckSubClaim, GateList,
+};
+use crate::gkr_round_sumcheck::wiring::{BinaryTree, ElementWise, Shift, WiringPredicate};
+use crate::gkr_round_sumcheck::zk::{GKRRoundMasks, TransparentMaskCommitment, ZKGKRRoundSumcheck};
+use crate::gkr_round_sumcheck::{
+    initialize_phase_one, initialize_phase_one_with_gates, initialize_phase_two,
+    initialize_phase_two_with_gates, GKRRoundSumcheck,
+};
 use crate::rng::{Blake2b512Rng, FeedableRNG};
 use ark_ff::Field;
-use ark_poly::{DenseMultilinearExtension, MultilinearExtension, SparseMultilinearExtension};
+use ark_poly::{
+    DenseMultilinearExtension, MultilinearExtension, Polynomial, SparseMultilinearExtension,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::rand::RngCore;
 use ark_std::{test_rng, UniformRand};
 use ark_test_curves::bls12_381::Fr;
 
+type Mask = TransparentMaskCommitment<Fr>;
+
 fn random_gkr_instance<F: Field, R: RngCore>(
     dim: usize,
     rng: &mut R,
@@ -14,10 +28,24 @@ fn random_gkr_instance<F: Field, R: RngCore>(
     DenseMultilinearExtension<F>,
     DenseMultilinearExtension<F>,
 ) {
+    random_gkr_instance_with_dims(dim, dim, dim, rng)
+}
+
+fn random_gkr_instance_with_dims<F: Field, R: RngCore>(
+    dim_g: usize,
+    dim_x: usize,
+    dim_y: usize,
+    rng: &mut R,
+) -> (
+    SparseMultilinearExtension<F>,
+    DenseMultilinearExtension<F>,
+    DenseMultilinearExtension<F>,
+) {
+    let num_gates = 1 << ark_std::cmp::max(dim_g, ark_std::cmp::max(dim_x, dim_y));
     (
-        SparseMultilinearExtension::rand_with_config(dim * 3, 1 << dim, rng),
-        DenseMultilinearExtension::rand(dim, rng),
-        DenseMultilinearExtension::rand(dim, rng),
+        SparseMultilinearExtension::rand_with_config(dim_g + dim_x + dim_y, num_gates, rng),
+        DenseMultilinearExtension::rand(dim_x, rng),
+        DenseMultilinearExtension::rand(dim_y, rng),
     )
 }
 
@@ -27,17 +55,16 @@ fn calculate_sum_naive<F: Field>(
     f3: &DenseMultilinearExtension<F>,
     g: &[F],
 ) -> F {
-    let dim = f2.num_vars;
-    assert_eq!(f1.num_vars, 3 * dim);
-    assert_eq!(f3.num_vars, dim);
+    let (dim_x, dim_y) = (f2.num_vars, f3.num_vars);
+    assert_eq!(f1.num_vars, g.len() + dim_x + dim_y);
     let f1_g = f1.fix_variables(g);
     let mut sum_xy = F::zero();
-    for x in 0..(1 << dim) {
+    for x in 0..(1 << dim_x) {
         let f2_x = f2[x];
         let f1_gx = f1_g
-            .fix_variables(&index_to_field_element(x, dim))
+            .fix_variables(&index_to_field_element(x, dim_x))
             .to_dense_multilinear_extension();
-        for y in 0..(1 << dim) {
+        for y in 0..(1 << dim_y) {
             sum_xy += f1_gx[y] * f2_x * f3[y];
         }
     }
@@ -62,17 +89,47 @@ fn test_circuit<F: Field>(nv: usize) {
     let mut rng = Blake2b512Rng::setup();
     let proof = GKRRoundSumcheck::prove(&mut rng, &f1, &f2, &f3, &g);
     rng = Blake2b512Rng::setup();
-    let subclaim = GKRRoundSumcheck::verify(&mut rng, f2.num_vars, &proof, claimed_sum)
-        .expect("verification failed");
+    let subclaim =
+        GKRRoundSumcheck::verify(&mut rng, f2.num_vars, f3.num_vars, &proof, claimed_sum)
+            .expect("verification failed");
     let result = subclaim.verify_subclaim(&f1, &f2, &f3, &g);
     assert!(result)
 }
 
+fn test_circuit_with_dims<F: Field>(dim_g: usize, dim_x: usize, dim_y: usize) {
+    let mut rng = test_rng();
+    let (f1, f2, f3) = random_gkr_instance_with_dims(dim_g, dim_x, dim_y, &mut rng);
+    let g: Vec<_> = (0..dim_g).map(|_| F::rand(&mut rng)).collect();
+    let claimed_sum = calculate_sum_naive(&f1, &f2, &f3, &g);
+    let gates = GateList::from_sparse_mle(&f1, dim_g, dim_x, dim_y);
+    for use_gates in [false, true] {
+        let mut rng = Blake2b512Rng::setup();
+        let proof = if use_gates {
+            GKRRoundSumcheck::prove_with_gates(&mut rng, &gates, &f2, &f3, &g)
+        } else {
+            GKRRoundSumcheck::prove(&mut rng, &f1, &f2, &f3, &g)
+        };
+        rng = Blake2b512Rng::setup();
+        let subclaim = GKRRoundSumcheck::verify(&mut rng, dim_x, dim_y, &proof, claimed_sum)
+            .expect("verification failed");
+        assert_eq!(subclaim.u.len(), dim_x);
+        assert_eq!(subclaim.v.len(), dim_y);
+        assert!(subclaim.verify_subclaim(&f1, &f2, &f3, &g));
+    }
+}
+
 #[test]
 fn test_small() {
     test_circuit::<Fr>(9);
 }
 
+#[test]
+fn test_different_widths() {
+    test_circuit_with_dims::<Fr>(2, 7, 7);
+    test_circuit_with_dims::<Fr>(6, 3, 5);
+    test_circuit_with_dims::<Fr>(0, 4, 2);
+}
+
 #[test]
 fn test_extract() {
     let nv = 6;
@@ -82,7 +139,633 @@ fn test_extract() {
     let expected_sum = calculate_sum_naive(&f1, &f2, &f3, &g);
     let mut rng = Blake2b512Rng::setup();
     let proof = GKRRoundSumcheck::prove(&mut rng, &f1, &f2, &f3, &g);
-    let actual_sum = proof.extract_sum();
+    let actual_sum = proof.extract_sum().unwrap();
 
     assert_eq!(actual_sum, expected_sum);
 }
+
+#[test]
+fn test_gate_list_initialization() {
+    let nv = 7;
+    let mut rng = test_rng();
+    let (f1, _, f3) = random_gkr_instance::<Fr, _>(nv, &mut rng);
+    let gates = GateList::from_sparse_mle(&f1, nv, nv, nv);
+    assert_eq!(gates.to_sparse_mle(), f1);
+
+    let g: Vec<_> = (0..nv).map(|_| Fr::rand(&mut rng)).collect();
+    let u: Vec<_> = (0..nv).map(|_| Fr::rand(&mut rng)).collect();
+    let (h_g, f1_g) = initialize_phase_one(&f1, &f3, &g);
+    let (h_g_gates, g_table) = initialize_phase_one_with_gates(&gates, &f3, &g);
+    assert_eq!(h_g, h_g_gates);
+    assert_eq!(
+        initialize_phase_two(&f1_g, &u),
+        initialize_phase_two_with_gates(&gates, &g_table, &u)
+    );
+}
+
+#[test]
+fn test_prove_with_gates() {
+    let nv = 9;
+    let mut rng = test_rng();
+    let (f1, f2, f3) = random_gkr_instance(nv, &mut rng);
+    let gates = GateList::from_sparse_mle(&f1, nv, nv, nv);
+    let g: Vec<_> = (0..nv).map(|_| Fr::rand(&mut rng)).collect();
+    let claimed_sum = calculate_sum_naive(&f1, &f2, &f3, &g);
+
+    let mut rng = Blake2b512Rng::setup();
+    let proof = GKRRoundSumcheck::prove_with_gates(&mut rng, &gates, &f2, &f3, &g);
+    assert_eq!(proof.extract_sum().unwrap(), claimed_sum);
+    rng = Blake2b512Rng::setup();
+    let subclaim = GKRRoundSumcheck::verify(&mut rng, nv, nv, &proof, claimed_sum)
+        .expect("verification failed");
+    assert!(subclaim.verify_subclaim(&f1, &f2, &f3, &g));
+}
+
+#[test]
+fn test_claim_reduction_after_round() {
+    // f2 = f3 = V as in GKR: the claims on V(u) and V(v) are reduced to a single claim
+    let nv = 6;
+    let mut rng = test_rng();
+    let (f1, f2, _) = random_gkr_instance(nv, &mut rng);
+    let g: Vec<_> = (0..nv).map(|_| Fr::rand(&mut rng)).collect();
+    let claimed_sum = calculate_sum_naive(&f1, &f2, &f2, &g);
+    let mut fs_rng = Blake2b512Rng::setup();
+    let proof = GKRRoundSumcheck::prove(&mut fs_rng, &f1, &f2, &f2, &g);
+    let points = {
+        let mut fs_rng = Blake2b512Rng::setup();
+        let subclaim = GKRRoundSumcheck::verify(&mut fs_rng, nv, nv, &proof, claimed_sum).unwrap();
+        vec![subclaim.u, subclaim.v]
+    };
+    let values: Vec<_> = points.iter().map(|p| f2.evaluate(p)).collect();
+
+    let (reduction_proof, prover_claim) = ClaimReduction::prove(&mut fs_rng, &f2, &points).unwrap();
+    let mut verifier_rng = Blake2b512Rng::setup();
+    GKRRoundSumcheck::verify(&mut verifier_rng, nv, nv, &proof, claimed_sum).unwrap();
+    let claim =
+        ClaimReduction::verify(&mut verifier_rng, &points, &values, &reduction_proof).unwrap();
+    assert_eq!(claim.point, prover_claim.point);
+    assert_eq!(f2.evaluate(&claim.point), claim.expected_evaluation);
+}
+
+#[test]
+fn test_claim_reduction() {
+    let nv = 5;
+    let mut rng = test_rng();
+    let poly = DenseMultilinearExtension::<Fr>::rand(nv, &mut rng);
+    for k in 1..5 {
+        let points: Vec<Vec<_>> = (0..k)
+            .map(|_| (0..nv).map(|_| Fr::rand(&mut rng)).collect())
+            .collect();
+        let mut values: Vec<_> = points.iter().map(|p| poly.evaluate(p)).collect();
+
+        let mut fs_rng = Blake2b512Rng::setup();
+        let (proof, _) = ClaimReduction::prove(&mut fs_rng, &poly, &points).unwrap();
+        let mut fs_rng = Blake2b512Rng::setup();
+        let claim = ClaimReduction::verify(&mut fs_rng, &points, &values, &proof).unwrap();
+        assert_eq!(poly.evaluate(&claim.point), claim.expected_evaluation);
+
+        // a wrong claimed value leads to a wrong reduced claim
+        values[k - 1] += Fr::from(1u64);
+        let mut fs_rng = Blake2b512Rng::setup();
+        let claim = ClaimReduction::verify(&mut fs_rng, &points, &values, &proof).unwrap();
+        assert_ne!(poly.evaluate(&claim.point), claim.expected_evaluation);
+
+        // the random linear combination of eq tables matches the combination of the values
+        let coefficients: Vec<_> = (0..k).map(|_| Fr::rand(&mut rng)).collect();
+        let combined: Fr = combine_eq_tables(&points, &coefficients)
+            .unwrap()
+            .iter()
+            .zip(poly.evaluations.iter())
+            .map(|(e, p)| *e * p)
+            .sum();
+        let expected: Fr = points
+            .iter()
+            .zip(&coefficients)
+            .map(|(p, c)| poly.evaluate(p) * c)
+            .sum();
+        assert_eq!(combined, expected);
+    }
+}
+
+#[test]
+fn test_claim_reduction_errors() {
+    let mut rng = test_rng();
+    let poly = DenseMultilinearExtension::<Fr>::rand(3, &mut rng);
+    let point = |nv: usize, rng: &mut _| -> Vec<Fr> { (0..nv).map(|_| Fr::rand(rng)).collect() };
+    let mismatched = vec![point(3, &mut rng), point(2, &mut rng)];
+    let wrong_dim = vec![point(2, &mut rng), point(2, &mut rng)];
+
+    let mut fs_rng = Blake2b512Rng::setup();
+    assert!(ClaimReduction::prove(&mut fs_rng, &poly, &[]).is_err());
+    assert!(ClaimReduction::prove(&mut fs_rng, &poly, &mismatched).is_err());
+    assert!(ClaimReduction::prove(&mut fs_rng, &poly, &wrong_dim).is_err());
+
+    let coefficients = vec![Fr::from(1u64); 2];
+    assert!(combine_eq_tables::<Fr>(&[], &[]).is_err());
+    assert!(combine_eq_tables(&mismatched, &coefficients).is_err());
+    assert!(combine_eq_tables(&wrong_dim, &coefficients[..1]).is_err());
+}
+
+fn test_structured_wiring<W: WiringPredicate<Fr>>(wiring: &W, gates: &GateList<Fr>) {
+    let mut rng = test_rng();
+    let (dim_g, dim_x, dim_y) = (gates.dim_g, gates.dim_x, gates.dim_y);
+    assert_eq!(wiring.num_vars(), dim_g + dim_x + dim_y);
+    let g: Vec<_> = (0..dim_g).map(|_| Fr::rand(&mut rng)).collect();
+    let u: Vec<_> = (0..dim_x).map(|_| Fr::rand(&mut rng)).collect();
+    let v: Vec<_> = (0..dim_y).map(|_| Fr::rand(&mut rng)).collect();
+    let f1 = gates.to_sparse_mle();
+    let guv: Vec<_> = g.iter().chain(&u).chain(&v).copied().collect();
+    assert_eq!(wiring.evaluate_wiring(&g, &u, &v), f1.evaluate(&guv));
+    assert_eq!(gates.evaluate_wiring(&g, &u, &v), f1.evaluate(&guv));
+    assert_eq!(f1.evaluate_wiring(&g, &u, &v), f1.evaluate(&guv));
+
+    // the succinct predicate verifies the subclaim of the round sumcheck
+    let f2 = DenseMultilinearExtension::rand(dim_x, &mut rng);
+    let f3 = DenseMultilinearExtension::rand(dim_y, &mut rng);
+    let mut fs_rng = Blake2b512Rng::setup();
+    let proof = GKRRoundSumcheck::prove_with_gates(&mut fs_rng, gates, &f2, &f3, &g);
+    let mut fs_rng = Blake2b512Rng::setup();
+    let subclaim = GKRRoundSumcheck::verify(
+        &mut fs_rng,
+        dim_x,
+        dim_y,
+        &proof,
+        proof.extract_sum().unwrap(),
+    )
+    .unwrap();
+    assert!(subclaim.verify_subclaim(wiring, &f2, &f3, &g));
+}
+
+#[test]
+fn test_wiring_predicates() {
+    for dim in 0..5 {
+        let tree = BinaryTree { dim };
+        test_structured_wiring(&tree, &tree.gate_list());
+        let element_wise = ElementWise { dim };
+        test_structured_wiring(&element_wise, &element_wise.gate_list());
+        for log_shift in 0..dim {
+            let shift = Shift { dim, log_shift };
+            test_structured_wiring(&shift, &shift.gate_list());
+        }
+    }
+}
+
+#[test]
+fn test_malformed_proofs() {
+    let mut rng = test_rng();
+    let (dim_g, dim_x, dim_y) = (3, 4, 2);
+    let (f1, f2, f3) = random_gkr_instance_with_dims(dim_g, dim_x, dim_y, &mut rng);
+    let g: Vec<_> = (0..dim_g).map(|_| Fr::rand(&mut rng)).collect();
+    let mut fs_rng = Blake2b512Rng::setup();
+    let proof = GKRRoundSumcheck::prove(&mut fs_rng, &f1, &f2, &f3, &g);
+    let claimed_sum = proof.extract_sum().unwrap();
+    let verify = |proof: &GKRProof<Fr>| {
+        let mut fs_rng = Blake2b512Rng::setup();
+        GKRRoundSumcheck::verify(&mut fs_rng, dim_x, dim_y, proof, claimed_sum)
+    };
+    assert!(verify(&proof).is_ok());
+
+    // truncated phases
+    let mut truncated = proof.clone();
+    truncated.phase1_sumcheck_msgs.pop();
+    assert!(verify(&truncated).is_err());
+    let mut truncated = proof.clone();
+    truncated.phase2_sumcheck_msgs.clear();
+    assert!(verify(&truncated).is_err());
+
+    // over-long phases
+    let mut over_long = proof.clone();
+    over_long
+        .phase1_sumcheck_msgs
+        .push(proof.phase1_sumcheck_msgs[0].clone());
+    assert!(verify(&over_long).is_err());
+    let mut over_long = proof.clone();
+    over_long
+        .phase2_sumcheck_msgs
+        .push(proof.phase2_sumcheck_msgs[0].clone());
+    assert!(verify(&over_long).is_err());
+
+    // messages with the wrong number of evaluations
+    let mut short_message = proof.clone();
+    short_message.phase1_sumcheck_msgs[2].evaluations.pop();
+    assert!(verify(&short_message).is_err());
+    let mut long_message = proof.clone();
+    long_message.phase2_sumcheck_msgs[1]
+        .evaluations
+        .push(Fr::from(1u64));
+    assert!(verify(&long_message).is_err());
+    let mut empty_message = proof;
+    empty_message.phase1_sumcheck_msgs[0].evaluations.clear();
+    assert!(verify(&empty_message).is_err());
+}
+
+#[test]
+fn test_phase_without_variables() {
+    let mut rng = test_rng();
+    let (f1, f2, f3) = random_gkr_instance_with_dims(2, 3, 1, &mut rng);
+    let g: Vec<_> = (0..2).map(|_| Fr::rand(&mut rng)).collect();
+    let mut fs_rng = Blake2b512Rng::setup();
+    let mut proof = GKRRoundSumcheck::prove(&mut fs_rng, &f1, &f2, &f3, &g);
+    proof.phase2_sumcheck_msgs.clear();
+
+    // a phase over zero variables has no message and keeps the claim of the previous phase
+    let mut fs_rng = Blake2b512Rng::setup();
+    let subclaim =
+        GKRRoundSumcheck::verify(&mut fs_rng, 3, 0, &proof, proof.extract_sum().unwrap()).unwrap();
+    assert_eq!(subclaim.u.len(), 3);
+    assert!(subclaim.v.is_empty());
+}
+
+#[test]
+fn test_bad_round_rejected_on_receipt() {
+    let mut rng = test_rng();
+    let (f1, f2, f3) = random_gkr_instance_with_dims(2, 4, 3, &mut rng);
+    let g: Vec<_> = (0..2).map(|_| Fr::rand(&mut rng)).collect();
+    let mut fs_rng = Blake2b512Rng::setup();
+    let mut proof = GKRRoundSumcheck::prove(&mut fs_rng, &f1, &f2, &f3, &g);
+    let claimed_sum = proof.extract_sum().unwrap();
+    proof.phase1_sumcheck_msgs[2].evaluations[0] += Fr::from(1u64);
+
+    let mut fs_rng = Blake2b512Rng::setup();
+    match GKRRoundSumcheck::verify(&mut fs_rng, 4, 3, &proof, claimed_sum) {
+        Err(crate::Error::Reject(Some(msg))) => assert!(msg.starts_with("Round 2:"), "{}", msg),
+        _ => panic!("a bad round should be rejected"),
+    }
+}
+
+#[test]
+fn test_x_without_variables() {
+    let mut rng = test_rng();
+    let (f1, f2, f3) = random_gkr_instance_with_dims(2, 0, 3, &mut rng);
+    let g: Vec<_> = (0..2).map(|_| Fr::rand(&mut rng)).collect();
+    let mut fs_rng = Blake2b512Rng::setup();
+    let proof = GKRRoundSumcheck::prove(&mut fs_rng, &f1, &f2, &f3, &g);
+    assert!(proof.phase1_sumcheck_msgs().is_empty());
+
+    // the sum is read from phase two
+    let claimed_sum = proof.extract_sum().unwrap();
+    assert_eq!(claimed_sum, calculate_sum_naive(&f1, &f2, &f3, &g));
+    let mut fs_rng = Blake2b512Rng::setup();
+    let subclaim = GKRRoundSumcheck::verify(&mut fs_rng, 0, 3, &proof, claimed_sum).unwrap();
+    assert!(subclaim.verify_subclaim(&f1, &f2, &f3, &g));
+
+    let empty = GKRProof::<Fr> {
+        phase1_sumcheck_msgs: Vec::new(),
+        phase2_sumcheck_msgs: Vec::new(),
+    };
+    assert!(empty.extract_sum().is_none());
+}
+
+#[test]
+fn test_proof_serialization() {
+    let mut rng = test_rng();
+    let (dim_g, dim_x, dim_y) = (3, 5, 4);
+    let (f1, f2, f3) = random_gkr_instance_with_dims(dim_g, dim_x, dim_y, &mut rng);
+    let g: Vec<_> = (0..dim_g).map(|_| Fr::rand(&mut rng)).collect();
+    let mut fs_rng = Blake2b512Rng::setup();
+    let proof = GKRRoundSumcheck::prove(&mut fs_rng, &f1, &f2, &f3, &g);
+    assert_eq!(proof.phase1_sumcheck_msgs().len(), dim_x);
+    assert_eq!(proof.phase2_sumcheck_msgs().len(), dim_y);
+    assert_eq!(proof.phase1_sumcheck_msgs()[0].evaluations().len(), 3);
+
+    let mut proof_bytes = Vec::new();
+    proof.serialize_compressed(&mut proof_bytes).unwrap();
+    let proof = GKRProof::<Fr>::deserialize_compressed(&proof_bytes[..]).unwrap();
+
+    let mut fs_rng = Blake2b512Rng::setup();
+    let subclaim = GKRRoundSumcheck::verify(
+        &mut fs_rng,
+        dim_x,
+        dim_y,
+        &proof,
+        proof.extract_sum().unwrap(),
+    )
+    .unwrap();
+    let mut bytes = Vec::new();
+    subclaim.serialize_compressed(&mut bytes).unwrap();
+    let subclaim = GKRRoundSumcheckSubClaim::<Fr>::deserialize_compressed(&bytes[..]).unwrap();
+    assert!(subclaim.verify_subclaim(&f1, &f2, &f3, &g));
+
+    // truncated bytes are rejected
+    assert!(GKRProof::<Fr>::deserialize_compressed(&proof_bytes[..proof_bytes.len() - 1]).is_err());
+}
+
+#[test]
+fn test_zk_round() {
+    let mut rng = test_rng();
+    for &(dim_g, dim_x, dim_y) in &[(3, 3, 3), (2, 1, 4), (4, 5, 1)] {
+        let (f1, f2, f3) = random_gkr_instance_with_dims(dim_g, dim_x, dim_y, &mut rng);
+        let g: Vec<_> = (0..dim_g).map(|_| Fr::rand(&mut rng)).collect();
+        let claimed_sum = calculate_sum_naive(&f1, &f2, &f3, &g);
+
+        let masks = GKRRoundMasks::rand(dim_x, dim_y, &mut rng);
+        let commitments = masks.commit::<Mask>(&()).unwrap();
+        let mut fs_rng = Blake2b512Rng::setup();
+        let (proof, openings) = ZKGKRRoundSumcheck::prove::<_, Mask>(
+            &mut fs_rng,
+            &(),
+            &masks,
+            &commitments,
+            &f1,
+            &f2,
+            &f3,
+            &g,
+        )
+        .unwrap();
+        let mut fs_rng = Blake2b512Rng::setup();
+        let subclaim = ZKGKRRoundSumcheck::verify::<_, Mask>(
+            &mut fs_rng,
+            dim_x,
+            dim_y,
+            &commitments,
+            &proof,
+            claimed_sum,
+        )
+        .expect("verification failed");
+        let f2_u = masks.evaluate_f2(&f2, &subclaim.u);
+        let f3_v = masks.evaluate_f3(&f3, &subclaim.v);
+        assert!(subclaim.verify_subclaim(&f1, &g, f2_u, f3_v));
+        assert!(subclaim
+            .verify_mask_openings::<Mask>(&(), &commitments, &openings)
+            .unwrap());
+
+        // a wrong sum is rejected
+        let mut fs_rng = Blake2b512Rng::setup();
+        let result = ZKGKRRoundSumcheck::verify::<_, Mask>(
+            &mut fs_rng,
+            dim_x,
+            dim_y,
+            &commitments,
+            &proof,
+            claimed_sum + Fr::from(1u64),
+        );
+        assert!(result.map_or(true, |s| !s.verify_subclaim(&f1, &g, f2_u, f3_v)));
+
+        // a wrong mask evaluation is rejected by the openings
+        let mut wrong_mask = subclaim.clone();
+        wrong_mask.phase2_mask_evaluation += Fr::from(1u64);
+        assert!(!wrong_mask
+            .verify_mask_openings::<Mask>(&(), &commitments, &openings)
+            .unwrap());
+
+        // the masked evaluations differ from the evaluations of f2 and f3
+        assert_ne!(
+            masks.evaluate_f2(&f2, &subclaim.u),
+            f2.evaluate(&subclaim.u)
+        );
+        assert_ne!(
+            masks.evaluate_f3(&f3, &subclaim.v),
+            f3.evaluate(&subclaim.v)
+        );
+    }
+}
+
+#[test]
+fn test_zk_round_masks_messages() {
+    let mut rng = test_rng();
+    let (f1, f2, f3) = random_gkr_instance_with_dims(3, 3, 3, &mut rng);
+    let g: Vec<_> = (0..3).map(|_| Fr::rand(&mut rng)).collect();
+
+    let prove = |rng: &mut _| {
+        let masks = GKRRoundMasks::rand(3, 3, rng);
+        let commitments = masks.commit::<Mask>(&()).unwrap();
+        let mut fs_rng = Blake2b512Rng::setup();
+        let (proof, _) = ZKGKRRoundSumcheck::prove::<_, Mask>(
+            &mut fs_rng,
+            &(),
+            &masks,
+            &commitments,
+            &f1,
+            &f2,
+            &f3,
+            &g,
+        )
+        .unwrap();
+        (proof, commitments)
+    };
+
+    // the same statement gives different messages under different masks
+    let (proof1, commitments) = prove(&mut rng);
+    let (proof2, _) = prove(&mut rng);
+    for (m1, m2) in proof1
+        .phase1_sumcheck_msgs()
+        .iter()
+        .zip(proof2.phase1_sumcheck_msgs())
+    {
+        assert_ne!(m1.evaluations(), m2.evaluations());
+    }
+
+    // malformed proofs are rejected
+    let mut truncated = proof1.clone();
+    truncated.phase2_sumcheck_msgs.pop();
+    let mut fs_rng = Blake2b512Rng::setup();
+    assert!(ZKGKRRoundSumcheck::verify::<_, Mask>(
+        &mut fs_rng,
+        3,
+        3,
+        &commitments,
+        &truncated,
+        Fr::from(0u64)
+    )
+    .is_err());
+    let mut short_message = proof1;
+    short_message.phase1_sumcheck_msgs[0].evaluations.pop();
+    let mut fs_rng = Blake2b512Rng::setup();
+    assert!(ZKGKRRoundSumcheck::verify::<_, Mask>(
+        &mut fs_rng,
+        3,
+        3,
+        &commitments,
+        &short_message,
+        Fr::from(0u64)
+    )
+    .is_err());
+
+    // masks of the wrong size and constant x are errors
+    let masks = GKRRoundMasks::rand(2, 3, &mut rng);
+    let commitments = masks.commit::<Mask>(&()).unwrap();
+    let mut fs_rng = Blake2b512Rng::setup();
+    assert!(ZKGKRRoundSumcheck::prove::<_, Mask>(
+        &mut fs_rng,
+        &(),
+        &masks,
+        &commitments,
+        &f1,
+        &f2,
+        &f3,
+        &g
+    )
+    .is_err());
+}
+
+#[test]
+fn test_discharge_subclaim_with_multi_point_opening() {
+    use crate::pcs::batch::{multi_point_open, multi_point_verify};
+    use crate::pcs::kzg::MultilinearKZG;
+    use crate::pcs::MultilinearPCS;
+    use ark_test_curves::bls12_381::Bls12_381;
+    type Kzg = MultilinearKZG<Bls12_381>;
+
+    let nv = 5;
+    let mut rng = test_rng();
+    let (f1, f2, f3) = random_gkr_instance::<Fr, _>(nv, &mut rng);
+    let g: Vec<_> = (0..nv).map(|_| Fr::rand(&mut rng)).collect();
+    let claimed_sum = calculate_sum_naive(&f1, &f2, &f3, &g);
+    let pp = Kzg::setup(nv, &mut rng).unwrap();
+    let commitments = vec![
+        Kzg::commit(&pp, &f2).unwrap(),
+        Kzg::commit(&pp, &f3).unwrap(),
+    ];
+
+    // the prover opens f2 at u and f3 at v with one opening, continuing the transcript
+    let mut fs_rng = Blake2b512Rng::setup();
+    let proof = GKRRoundSumcheck::prove(&mut fs_rng, &f1, &f2, &f3, &g);
+    let mut verifier_rng = Blake2b512Rng::setup();
+    let subclaim =
+        GKRRoundSumcheck::verify(&mut verifier_rng, nv, nv, &proof, claimed_sum).unwrap();
+    let points = vec![subclaim.u.clone(), subclaim.v.clone()];
+    let values = vec![f2.evaluate(&subclaim.u), f3.evaluate(&subclaim.v)];
+    let opening = multi_point_open::<_, Kzg>(
+        &mut fs_rng,
+        &pp,
+        &[f2.clone(), f3.clone()],
+        &commitments,
+        &points,
+    )
+    .unwrap();
+
+    assert_eq!(
+        f1.evaluate_wiring(&g, &subclaim.u, &subclaim.v) * values[0] * values[1],
+        subclaim.expected_evaluation
+    );
+    assert!(multi_point_verify::<_, Kzg>(
+        &mut verifier_rng,
+        &pp,
+        &commitments,
+        &points,
+        &values,
+        &opening
+    )
+    .unwrap());
+}
+
+#[test]
+fn test_compressed_proof() {
+    let mut rng = test_rng();
+    for (dim_g, dim_x, dim_y) in [(3, 5, 4), (2, 3, 1)] {
+        let (f1, f2, f3) = random_gkr_instance_with_dims(dim_g, dim_x, dim_y, &mut rng);
+        let g: Vec<_> = (0..dim_g).map(|_| Fr::rand(&mut rng)).collect();
+        let mut fs_rng = Blake2b512Rng::setup();
+        let proof = GKRRoundSumcheck::prove(&mut fs_rng, &f1, &f2, &f3, &g);
+        let claimed_sum = proof.extract_sum().unwrap();
+
+        let compressed = proof.compress();
+        let mut bytes = Vec::new();
+        compressed.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(
+            proof.compressed_size() - bytes.len(),
+            (dim_x + dim_y) * claimed_sum.compressed_size()
+        );
+        let compressed = CompressedGKRProof::<Fr>::deserialize_compressed(&bytes[..]).unwrap();
+
+        let mut fs_rng = Blake2b512Rng::setup();
+        let subclaim =
+            GKRRoundSumcheck::verify(&mut fs_rng, dim_x, dim_y, &proof, claimed_sum).unwrap();
+        let mut fs_rng = Blake2b512Rng::setup();
+        let compressed_subclaim = GKRRoundSumcheck::verify_compressed(
+            &mut fs_rng,
+            dim_x,
+            dim_y,
+            &compressed,
+            claimed_sum,
+        )
+        .unwrap();
+        assert_eq!(subclaim.u, compressed_subclaim.u);
+        assert_eq!(subclaim.v, compressed_subclaim.v);
+        assert_eq!(
+            subclaim.expected_evaluation,
+            compressed_subclaim.expected_evaluation
+        );
+        assert!(compressed_subclaim.verify_subclaim(&f1, &f2, &f3, &g));
+
+        let mut fs_rng = Blake2b512Rng::setup();
+        let wrong_sum = GKRRoundSumcheck::verify_compressed(
+            &mut fs_rng,
+            dim_x,
+            dim_y,
+            &compressed,
+            claimed_sum + Fr::from(1u64),
+        )
+        .unwrap();
+        assert!(!wrong_sum.verify_subclaim(&f1, &f2, &f3, &g));
+    }
+}
+
+#[test]
+fn test_malformed_compressed_proofs() {
+    let mut rng = test_rng();
+    let (dim_g, dim_x, dim_y) = (3, 4, 2);
+    let (f1, f2, f3) = random_gkr_instance_with_dims(dim_g, dim_x, dim_y, &mut rng);
+    let g: Vec<_> = (0..dim_g).map(|_| Fr::rand(&mut rng)).collect();
+    let mut fs_rng = Blake2b512Rng::setup();
+    let proof = GKRRoundSumcheck::prove(&mut fs_rng, &f1, &f2, &f3, &g);
+    let claimed_sum = proof.extract_sum().unwrap();
+    let compressed = proof.compress();
+    let verify = |proof: &CompressedGKRProof<Fr>| {
+        let mut fs_rng = Blake2b512Rng::setup();
+        GKRRoundSumcheck::verify_compressed(&mut fs_rng, dim_x, dim_y, proof, claimed_sum)
+    };
+    assert!(verify(&compressed).is_ok());
+
+    let mut truncated = compressed.clone();
+    truncated.phase1_sumcheck_msgs.pop();
+    assert!(verify(&truncated).is_err());
+    let mut over_long = compressed.clone();
+    over_long
+        .phase2_sumcheck_msgs
+        .push(compressed.phase2_sumcheck_msgs[0].clone());
+    assert!(verify(&over_long).is_err());
+    let mut long_message = compressed.clone();
+    long_message.phase2_sumcheck_msgs[1]
+        .evaluations
+        .push(Fr::from(1u64));
+    assert!(verify(&long_message).is_err());
+
+    let mut tampered = compressed;
+    tampered.phase1_sumcheck_msgs[1].evaluations[0] += Fr::from(1u64);
+    let subclaim = verify(&tampered).unwrap();
+    assert!(!subclaim.verify_subclaim(&f1, &f2, &f3, &g));
+}
+
+#[test]
+fn test_sparse_phase_two() {
+    let mut rng = test_rng();
+    let (dim_g, dim_x, dim_y) = (4, 5, 7);
+    let (f1, f2, f3) = random_gkr_instance_with_dims(dim_g, dim_x, dim_y, &mut rng);
+    let gates = GateList::from_sparse_mle(&f1, dim_g, dim_x, dim_y);
+    let g: Vec<_> = (0..dim_g).map(|_| Fr::rand(&mut rng)).collect();
+
+    // phase two of `prove` keeps f1 sparse, while `prove_with_gates` builds its dense table
+    let mut fs_rng = Blake2b512Rng::setup();
+    let proof = GKRRoundSumcheck::prove(&mut fs_rng, &f1, &f2, &f3, &g);
+    let mut fs_rng = Blake2b512Rng::setup();
+    let dense_proof = GKRRoundSumcheck::prove_with_gates(&mut fs_rng, &gates, &f2, &f3, &g);
+    for (msg, dense_msg) in proof
+        .phase2_sumcheck_msgs()
+        .iter()
+        .zip(dense_proof.phase2_sumcheck_msgs())
+    {
+        assert_eq!(msg.evaluations(), dense_msg.evaluations());
+    }
+
+    let mut fs_rng = Blake2b512Rng::setup();
+    let subclaim = GKRRoundSumcheck::verify(
+        &mut fs_rng,
+        dim_x,
+        dim_y,
+        &proof,
+        proof.extract_sum().unwrap(),
+    )
+    .unwrap();
+    assert!(subclaim.verify_subclaim(&f1, &f2, &f3, &g));
+}
@@ -0,0 +1,187 @@
+//! Wiring predicates of GKR round functions.
+//!
+//! The verifier of a GKR round needs `f1(g, u, v)`. Evaluating the multilinear extension of an
+//! arbitrary `f1` costs time linear in the number of gates, but regular layers have wiring
+//! predicates that can be evaluated in time linear in the number of variables, as noted in
+//! [Tha13](https://arxiv.org/abs/1304.3812) (Section 3).
+
+use crate::gkr_round_sumcheck::data_structures::GateList;
+use ark_ff::Field;
+use ark_poly::{Polynomial, SparseMultilinearExtension};
+use ark_std::vec::Vec;
+
+/// Wiring predicate `f1(g, x, y)` of a GKR round function that the verifier can evaluate.
+pub trait WiringPredicate<F: Field> {
+    /// Returns the total number of variables of `g`, `x` and `y`.
+    fn num_vars(&self) -> usize;
+
+    /// Evaluates the multilinear extension of the predicate at `(g, u, v)`.
+    fn evaluate_wiring(&self, g: &[F], u: &[F], v: &[F]) -> F;
+}
+
+/// Fallback for arbitrary predicates, in time linear in the number of non-zero entries
+impl<F: Field> WiringPredicate<F> for SparseMultilinearExtension<F> {
+    fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    fn evaluate_wiring(&self, g: &[F], u: &[F], v: &[F]) -> F {
+        assert_eq!(self.num_vars, g.len() + u.len() + v.len());
+        let guv: Vec<_> = g.iter().chain(u).chain(v).copied().collect();
+        self.evaluate(&guv)
+    }
+}
+
+impl<F: Field> WiringPredicate<F> for GateList<F> {
+    fn num_vars(&self) -> usize {
+        self.dim_g + self.dim_x + self.dim_y
+    }
+
+    fn evaluate_wiring(&self, g: &[F], u: &[F], v: &[F]) -> F {
+        assert_eq!(
+            (g.len(), u.len(), v.len()),
+            (self.dim_g, self.dim_x, self.dim_y)
+        );
+        let g_table = crate::utils::eq_evaluations(g);
+        let u_table = crate::utils::eq_evaluations(u);
+        let v_table = crate::utils::eq_evaluations(v);
+        self.gates
+            .iter()
+            .map(|&(z, x, y, c)| c * g_table[z] * u_table[x] * v_table[y])
+            .sum()
+    }
+}
+
+/// `eq(a, b, c)`, i.e. the multilinear extension of `a = b = c` over bits.
+fn eq3<F: Field>(a: F, b: F, c: F) -> F {
+    a * b * c + (F::one() - a) * (F::one() - b) * (F::one() - c)
+}
+
+/// Layer of a binary tree: gate `z` reads `2z` and `2z + 1` of the layer below.
+///
+/// `g` has `dim` variables and `x`, `y` have `dim + 1` variables.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BinaryTree {
+    /// number of variables of g
+    pub dim: usize,
+}
+
+impl BinaryTree {
+    /// Returns the non-zero entries of the predicate, for the prover.
+    pub fn gate_list<F: Field>(&self) -> GateList<F> {
+        let mut gates = GateList::new(self.dim, self.dim + 1, self.dim + 1);
+        for z in 0..(1 << self.dim) {
+            gates.add_gate(z, 2 * z, 2 * z + 1, F::one());
+        }
+        gates
+    }
+}
+
+impl<F: Field> WiringPredicate<F> for BinaryTree {
+    fn num_vars(&self) -> usize {
+        3 * self.dim + 2
+    }
+
+    fn evaluate_wiring(&self, g: &[F], u: &[F], v: &[F]) -> F {
+        assert_eq!(
+            (g.len(), u.len(), v.len()),
+            (self.dim, self.dim + 1, self.dim + 1)
+        );
+        // bit 0 selects the left or right child, and the higher bits are z
+        (F::one() - u[0])
+            * v[0]
+            * (0..self.dim)
+                .map(|i| eq3(g[i], u[i + 1], v[i + 1]))
+                .product::<F>()
+    }
+}
+
+/// Element-wise layer: the layer below holds two vectors `a` and `b` of `2^dim` values, and gate
+/// `z` reads `a[z]` and `b[z]`.
+///
+/// `g` has `dim` variables and `x`, `y` have `dim + 1` variables.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ElementWise {
+    /// number of variables of g
+    pub dim: usize,
+}
+
+impl ElementWise {
+    /// Returns the non-zero entries of the predicate, for the prover.
+    pub fn gate_list<F: Field>(&self) -> GateList<F> {
+        let mut gates = GateList::new(self.dim, self.dim + 1, self.dim + 1);
+        for z in 0..(1 << self.dim) {
+            gates.add_gate(z, z, z + (1 << self.dim), F::one());
+        }
+        gates
+    }
+}
+
+impl<F: Field> WiringPredicate<F> for ElementWise {
+    fn num_vars(&self) -> usize {
+        3 * self.dim + 2
+    }
+
+    fn evaluate_wiring(&self, g: &[F], u: &[F], v: &[F]) -> F {
+        let dim = self.dim;
+        assert_eq!((g.len(), u.len(), v.len()), (dim, dim + 1, dim + 1));
+        // the highest bit selects a or b
+        (F::one() - u[dim]) * v[dim] * (0..dim).map(|i| eq3(g[i], u[i], v[i])).product::<F>()
+    }
+}
+
+/// Shift layer: gate `z` reads `z` and `z + 2^log_shift mod 2^dim` of a layer of the same size.
+///
+/// `g`, `x` and `y` have `dim` variables.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Shift {
+    /// number of variables of g, x and y
+    pub dim: usize,
+    /// the shift is `2^log_shift`
+    pub log_shift: usize,
+}
+
+impl Shift {
+    /// Returns the non-zero entries of the predicate, for the prover.
+    pub fn gate_list<F: Field>(&self) -> GateList<F> {
+        assert!(self.log_shift < self.dim);
+        let mut gates = GateList::new(self.dim, self.dim, self.dim);
+        let mask = (1 << self.dim) - 1;
+        for z in 0..(1 << self.dim) {
+            gates.add_gate(z, z, (z + (1 << self.log_shift)) & mask, F::one());
+        }
+        gates
+    }
+}
+
+impl<F: Field> WiringPredicate<F> for Shift {
+    fn num_vars(&self) -> usize {
+        3 * self.dim
+    }
+
+    fn evaluate_wiring(&self, g: &[F], u: &[F], v: &[F]) -> F {
+        let (dim, j) = (self.dim, self.log_shift);
+        assert!(j < dim);
+        assert_eq!((g.len(), u.len(), v.len()), (dim, dim, dim));
+        // bits below j are equal, and bits j.. of y are bits j.. of z incremented by one
+        let low: F = (0..j).map(|i| eq3(g[i], u[i], v[i])).product();
+        let one_zero = |i: usize| g[i] * u[i] * (F::one() - v[i]);
+        let zero_one = |i: usize| (F::one() - g[i]) * (F::one() - u[i]) * v[i];
+
+        // suffix[k] = prod_{i >= k} eq3(g_i, u_i, v_i)
+        let mut suffix = vec![F::one(); dim + 1];
+        for i in (j..dim).rev() {
+            suffix[i] = suffix[i + 1] * eq3(g[i], u[i], v[i]);
+        }
+        // the carry stops at bit k: bits j..k of z are one, bit k is zero
+        let mut carry = F::one();
+        let mut increment = F::zero();
+        for k in j..dim {
+            increment += carry * zero_one(k) * suffix[k + 1];
+            carry *= one_zero(k);
+        }
+        // z >> j is all ones and wraps around to zero
+        increment += carry;
+        low * increment
+    }
+}
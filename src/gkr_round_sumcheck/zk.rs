@@ -0,0 +1,375 @@
+//! Zero-knowledge GKR round sumcheck, as described in
+//! [XZZPS19](https://eprint.iacr.org/2019/317.pdf#section.4) (Section 4).
+//!
+//! Both phases are masked: before the sumcheck over x, the prover sends the sum `M` of a random
+//! polynomial `m(x) = a_0 + m_1(x_1) + ... + m_n(x_n)`, the verifier samples `rho` and both parties
+//! run the sumcheck on `f + rho * m`, whose sum is `H + rho * M`.
+//!
+//! `f2` and `f3` are replaced by `f2'(x) = f2(x) + c_2 Z(x)` and `f3'(y) = f3(y) + c_3 Z(y)`, where
+//! `Z(x) = prod_i x_i (1 - x_i)` vanishes on the boolean hypercube. The sum is unchanged, but the
+//! final evaluations `f2'(u)` and `f3'(v)` do not reveal `f2(u)` and `f3(v)`. As `Z` is zero when
+//! any of its variables is boolean, only the last round of each phase depends on the randomizers.
+//! The round polynomials have degree 3.
+//!
+//! The prover commits to the masks with a `MaskCommitmentScheme` before running the protocol, and
+//! opens them at the points of the subclaim, so that the verifier only learns `m(u)` and the
+//! masked evaluations `f2'(u)` and `f3'(v)`.
+
+use crate::gkr_round_sumcheck::wiring::WiringPredicate;
+use crate::gkr_round_sumcheck::{initialize_phase_one, initialize_phase_two, GKRRoundSumcheck};
+use crate::ml_sumcheck::data_structures::SumOfUnivariates;
+pub use crate::ml_sumcheck::data_structures::{MaskCommitmentScheme, TransparentMaskCommitment};
+use crate::ml_sumcheck::protocol::prover::ProverMsg;
+use crate::ml_sumcheck::protocol::verifier::interpolate_uni_poly;
+use crate::ml_sumcheck::protocol::{IPForMLSumcheck, ListOfProductsOfPolynomials};
+use crate::rng::FeedableRNG;
+use ark_ff::Field;
+use ark_poly::{
+    DenseMultilinearExtension, MultilinearExtension, Polynomial, SparseMultilinearExtension,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::marker::PhantomData;
+use ark_std::rand::RngCore;
+use ark_std::sync::Arc;
+use ark_std::vec::Vec;
+
+/// Degree of the round polynomials of the zero-knowledge sumcheck
+const DEGREE: usize = 3;
+
+/// Evaluates `Z(x) = prod_i x_i (1 - x_i)`, which is zero on the boolean hypercube.
+pub fn vanishing_eval<F: Field>(point: &[F]) -> F {
+    point.iter().map(|x| *x * (F::one() - x)).product()
+}
+
+/// Openings of the masks of both phases, at u and v
+pub type GKRRoundMaskOpenings<F, M> = (
+    <M as MaskCommitmentScheme<F>>::Proof,
+    <M as MaskCommitmentScheme<F>>::Proof,
+);
+
+/// Random masks of the prover, that must be committed before the protocol.
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct GKRRoundMasks<F: Field> {
+    /// `c_2` such that `f2'(x) = f2(x) + c_2 Z(x)`
+    pub f2_randomizer: F,
+    /// `c_3` such that `f3'(y) = f3(y) + c_3 Z(y)`
+    pub f3_randomizer: F,
+    /// mask of the sumcheck over x
+    pub phase1_mask: SumOfUnivariates<F>,
+    /// mask of the sumcheck over y
+    pub phase2_mask: SumOfUnivariates<F>,
+}
+
+impl<F: Field> GKRRoundMasks<F> {
+    /// Samples masks for a GKR round function where x has `dim_x` variables and y has `dim_y`
+    /// variables.
+    pub fn rand<R: RngCore>(dim_x: usize, dim_y: usize, rng: &mut R) -> Self {
+        Self {
+            f2_randomizer: F::rand(rng),
+            f3_randomizer: F::rand(rng),
+            phase1_mask: SumOfUnivariates::rand(dim_x, DEGREE, rng),
+            phase2_mask: SumOfUnivariates::rand(dim_y, DEGREE, rng),
+        }
+    }
+
+    /// Commits to the masks of both phases.
+    pub fn commit<M: MaskCommitmentScheme<F>>(
+        &self,
+        pp: &M::Parameters,
+    ) -> Result<(M::Commitment, M::Commitment), crate::Error> {
+        Ok((
+            M::commit(pp, &self.phase1_mask)?,
+            M::commit(pp, &self.phase2_mask)?,
+        ))
+    }
+
+    /// Evaluates `f2'(u) = f2(u) + c_2 Z(u)`.
+    pub fn evaluate_f2(&self, f2: &DenseMultilinearExtension<F>, u: &[F]) -> F {
+        f2.evaluate(&u.to_vec()) + self.f2_randomizer * vanishing_eval(u)
+    }
+
+    /// Evaluates `f3'(v) = f3(v) + c_3 Z(v)`.
+    pub fn evaluate_f3(&self, f3: &DenseMultilinearExtension<F>, v: &[F]) -> F {
+        f3.evaluate(&v.to_vec()) + self.f3_randomizer * vanishing_eval(v)
+    }
+}
+
+/// Proof of the zero-knowledge GKR round sumcheck
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ZKGKRProof<F: Field> {
+    pub(crate) phase1_mask_sum: F,
+    pub(crate) phase1_sumcheck_msgs: Vec<ProverMsg<F>>,
+    pub(crate) phase1_mask_evaluation: F,
+    pub(crate) phase2_mask_sum: F,
+    pub(crate) phase2_sumcheck_msgs: Vec<ProverMsg<F>>,
+    pub(crate) phase2_mask_evaluation: F,
+}
+
+impl<F: Field> ZKGKRProof<F> {
+    /// Returns the prover messages of the sumcheck over x.
+    pub fn phase1_sumcheck_msgs(&self) -> &[ProverMsg<F>] {
+        &self.phase1_sumcheck_msgs
+    }
+
+    /// Returns the prover messages of the sumcheck over y.
+    pub fn phase2_sumcheck_msgs(&self) -> &[ProverMsg<F>] {
+        &self.phase2_sumcheck_msgs
+    }
+}
+
+/// Subclaim of the zero-knowledge GKR round sumcheck
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ZKGKRRoundSumcheckSubClaim<F: Field> {
+    /// u
+    pub u: Vec<F>,
+    /// v
+    pub v: Vec<F>,
+    /// expected evaluation of `f1(g,u,v) f2'(u) f3'(v)`
+    pub expected_evaluation: F,
+    /// claimed evaluation of the phase one mask at u
+    pub phase1_mask_evaluation: F,
+    /// claimed evaluation of the phase two mask at v
+    pub phase2_mask_evaluation: F,
+}
+
+impl<F: Field> ZKGKRRoundSumcheckSubClaim<F> {
+    /// Verify that the subclaim is true, given the claimed masked evaluations `f2_u = f2'(u)` and
+    /// `f3_v = f3'(v)`.
+    ///
+    /// The masked evaluations are claims on the next layer, and the claimed mask evaluations must
+    /// be checked with `verify_mask_openings`.
+    pub fn verify_subclaim(&self, f1: &impl WiringPredicate<F>, g: &[F], f2_u: F, f3_v: F) -> bool {
+        assert_eq!(f1.num_vars(), g.len() + self.u.len() + self.v.len());
+        f1.evaluate_wiring(g, &self.u, &self.v) * f2_u * f3_v == self.expected_evaluation
+    }
+
+    /// Checks the claimed evaluations of the masks at u and v against the commitments of the
+    /// prover, given the openings of `ZKGKRRoundSumcheck::prove`.
+    ///
+    /// Returns an error if an opening is malformed.
+    pub fn verify_mask_openings<M: MaskCommitmentScheme<F>>(
+        &self,
+        pp: &M::Parameters,
+        (phase1_commitment, phase2_commitment): &(M::Commitment, M::Commitment),
+        (phase1_opening, phase2_opening): &GKRRoundMaskOpenings<F, M>,
+    ) -> Result<bool, crate::Error> {
+        Ok(M::verify(
+            pp,
+            phase1_commitment,
+            &self.u,
+            self.phase1_mask_evaluation,
+            phase1_opening,
+        )? && M::verify(
+            pp,
+            phase2_commitment,
+            &self.v,
+            self.phase2_mask_evaluation,
+            phase2_opening,
+        )?)
+    }
+}
+
+/// Zero-knowledge sumcheck argument for GKR round function
+pub struct ZKGKRRoundSumcheck<F: Field> {
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> ZKGKRRoundSumcheck<F> {
+    /// Takes a GKR Round Function and input, and proves the sum in zero knowledge.
+    /// * `fs_rng`: transcript shared with the verifier
+    /// * `pp`: parameters of the mask commitment scheme
+    /// * `masks`: masks sampled by `GKRRoundMasks::rand`
+    /// * `commitments`: commitments to the masks, from `GKRRoundMasks::commit`
+    /// * `f1`,`f2`,`f3`: represents the GKR round function
+    /// * `g`: represents the fixed input.
+    ///
+    /// Returns the proof along with the openings of the masks at u and v.
+    #[allow(clippy::too_many_arguments)]
+    pub fn prove<R: FeedableRNG<Error = crate::Error>, M: MaskCommitmentScheme<F>>(
+        fs_rng: &mut R,
+        pp: &M::Parameters,
+        masks: &GKRRoundMasks<F>,
+        commitments: &(M::Commitment, M::Commitment),
+        f1: &SparseMultilinearExtension<F>,
+        f2: &DenseMultilinearExtension<F>,
+        f3: &DenseMultilinearExtension<F>,
+        g: &[F],
+    ) -> Result<(ZKGKRProof<F>, GKRRoundMaskOpenings<F, M>), crate::Error> {
+        let (dim_x, dim_y) = (f2.num_vars, f3.num_vars);
+        if f1.num_vars != g.len() + dim_x + dim_y {
+            return Err(crate::Error::OtherError(
+                "f1 does not match the dimensions of g, f2 and f3.".into(),
+            ));
+        }
+        if dim_x == 0 || dim_y == 0 {
+            return Err(crate::Error::OtherError(
+                "x and y should have at least one variable.".into(),
+            ));
+        }
+        if masks.phase1_mask.num_vars() != dim_x || masks.phase2_mask.num_vars() != dim_y {
+            return Err(crate::Error::OtherError(
+                "Masks do not match the dimensions of f2 and f3.".into(),
+            ));
+        }
+        fs_rng.feed(commitments)?;
+
+        let (h_g, f1_g) = initialize_phase_one(f1, f3, g);
+        let phase1_mask_sum = masks.phase1_mask.sum_over_hypercube();
+        fs_rng.feed(&phase1_mask_sum)?;
+        let rho1 = F::rand(fs_rng);
+        let (phase1_sumcheck_msgs, u) = Self::prove_masked_phase(
+            fs_rng,
+            &h_g,
+            f2,
+            masks.f2_randomizer,
+            &masks.phase1_mask,
+            rho1,
+        )?;
+        let phase1_mask_evaluation = masks.phase1_mask.evaluate(&u);
+        fs_rng.feed(&phase1_mask_evaluation)?;
+
+        let f2_u = masks.evaluate_f2(f2, &u);
+        let f1_gu = initialize_phase_two(&f1_g, &u);
+        let f1_gu_f2u = DenseMultilinearExtension::from_evaluations_vec(
+            dim_y,
+            f1_gu.evaluations.iter().map(|e| *e * f2_u).collect(),
+        );
+        let phase2_mask_sum = masks.phase2_mask.sum_over_hypercube();
+        fs_rng.feed(&phase2_mask_sum)?;
+        let rho2 = F::rand(fs_rng);
+        let (phase2_sumcheck_msgs, v) = Self::prove_masked_phase(
+            fs_rng,
+            &f1_gu_f2u,
+            f3,
+            masks.f3_randomizer,
+            &masks.phase2_mask,
+            rho2,
+        )?;
+        let phase2_mask_evaluation = masks.phase2_mask.evaluate(&v);
+        fs_rng.feed(&phase2_mask_evaluation)?;
+
+        let openings = (
+            M::open(pp, &masks.phase1_mask, &u)?,
+            M::open(pp, &masks.phase2_mask, &v)?,
+        );
+        Ok((
+            ZKGKRProof {
+                phase1_mask_sum,
+                phase1_sumcheck_msgs,
+                phase1_mask_evaluation,
+                phase2_mask_sum,
+                phase2_sumcheck_msgs,
+                phase2_mask_evaluation,
+            },
+            openings,
+        ))
+    }
+
+    /// Proves the sum of `a(x) (b(x) + randomizer * Z(x)) + rho * mask(x)`, and returns the prover
+    /// messages along with the sampled point.
+    fn prove_masked_phase<R: FeedableRNG<Error = crate::Error>>(
+        fs_rng: &mut R,
+        a: &DenseMultilinearExtension<F>,
+        b: &DenseMultilinearExtension<F>,
+        randomizer: F,
+        mask: &SumOfUnivariates<F>,
+        rho: F,
+    ) -> Result<(Vec<ProverMsg<F>>, Vec<F>), crate::Error> {
+        let nv = a.num_vars;
+        let mut poly = ListOfProductsOfPolynomials::new(nv);
+        poly.add_product(vec![Arc::new(a.clone()), Arc::new(b.clone())], F::one());
+        let mut ps = IPForMLSumcheck::prover_init(&poly);
+        let mut vm = None;
+        let mut msgs = Vec::with_capacity(nv);
+        let mut point = Vec::with_capacity(nv);
+        for round in 0..nv {
+            let mut evaluations = IPForMLSumcheck::prove_round(&mut ps, &vm).evaluations;
+            evaluations.push(interpolate_uni_poly(&evaluations, F::from(3u64)));
+            if round == nv - 1 {
+                // Z(point, t) = Z(point) t (1 - t), and a(point, t) is linear in t
+                let a_r = a.fix_variables(&point);
+                let z = randomizer * vanishing_eval(&point);
+                for (t, e) in evaluations.iter_mut().enumerate() {
+                    let t = F::from(t as u64);
+                    *e += z * t * (F::one() - t) * (a_r[0] + t * (a_r[1] - a_r[0]));
+                }
+            }
+            let mask_evaluations = mask.round_evaluations(&point, DEGREE + 1);
+            for (e, m) in evaluations.iter_mut().zip(mask_evaluations) {
+                *e += rho * m;
+            }
+
+            let pm = ProverMsg { evaluations };
+            fs_rng.feed(&pm)?;
+            msgs.push(pm);
+            let msg = IPForMLSumcheck::sample_round(fs_rng);
+            point.push(msg.randomness);
+            vm = Some(msg);
+        }
+        Ok((msgs, point))
+    }
+
+    /// Takes the dimensions, the commitments to the masks, the claimed sum and the proof, and
+    /// returns a subclaim on the masked GKR Round function and the masks.
+    ///
+    /// If the `claimed_sum` is correct, then `subclaim.verify_subclaim` will return true.
+    /// Otherwise, it is very likely that `subclaim.verify_subclaim` will return false.
+    /// * `dim_x`: represents number of variables of f2
+    /// * `dim_y`: represents number of variables of f3
+    /// * `commitments`: commitments to the masks, sent by the prover before the protocol
+    pub fn verify<R: FeedableRNG<Error = crate::Error>, M: MaskCommitmentScheme<F>>(
+        fs_rng: &mut R,
+        dim_x: usize,
+        dim_y: usize,
+        commitments: &(M::Commitment, M::Commitment),
+        proof: &ZKGKRProof<F>,
+        claimed_sum: F,
+    ) -> Result<ZKGKRRoundSumcheckSubClaim<F>, crate::Error> {
+        GKRRoundSumcheck::check_phase_shape(
+            "Phase one",
+            dim_x,
+            DEGREE,
+            &proof.phase1_sumcheck_msgs,
+        )?;
+        GKRRoundSumcheck::check_phase_shape(
+            "Phase two",
+            dim_y,
+            DEGREE,
+            &proof.phase2_sumcheck_msgs,
+        )?;
+
+        fs_rng.feed(commitments)?;
+        fs_rng.feed(&proof.phase1_mask_sum)?;
+        let rho1 = F::rand(fs_rng);
+        let phase1_subclaim = GKRRoundSumcheck::verify_phase(
+            fs_rng,
+            dim_x,
+            DEGREE,
+            &proof.phase1_sumcheck_msgs,
+            claimed_sum + rho1 * proof.phase1_mask_sum,
+        )?;
+        fs_rng.feed(&proof.phase1_mask_evaluation)?;
+        let phase2_claim =
+            phase1_subclaim.expected_evaluation - rho1 * proof.phase1_mask_evaluation;
+
+        fs_rng.feed(&proof.phase2_mask_sum)?;
+        let rho2 = F::rand(fs_rng);
+        let phase2_subclaim = GKRRoundSumcheck::verify_phase(
+            fs_rng,
+            dim_y,
+            DEGREE,
+            &proof.phase2_sumcheck_msgs,
+            phase2_claim + rho2 * proof.phase2_mask_sum,
+        )?;
+        fs_rng.feed(&proof.phase2_mask_evaluation)?;
+
+        Ok(ZKGKRRoundSumcheckSubClaim {
+            u: phase1_subclaim.point,
+            v: phase2_subclaim.point,
+            expected_evaluation: phase2_subclaim.expected_evaluation
+                - rho2 * proof.phase2_mask_evaluation,
+            phase1_mask_evaluation: proof.phase1_mask_evaluation,
+            phase2_mask_evaluation: proof.phase2_mask_evaluation,
+        })
+    }
+}
@@ -11,7 +11,9 @@ use ark_std::rand::Rng;
 #[cfg(test)]
 mod test;
 
+use crate::gkr_round_sumcheck::wiring::WiringPredicate;
 use crate::gkr_round_sumcheck::{initialize_phase_one, initialize_phase_two};
+use crate::pcs::MultilinearPCS;
 use crate::sumfold::{utils::product_f, SumFoldInstance, SumFoldProof};
 
 /// A GKRFold instance is a tuple of three multilinear extensions and a vector of field elements.
@@ -27,6 +29,27 @@ pub struct GKRFoldInstance<F: Field> {
     pub g: Vec<F>,
 }
 
+impl<F: Field> GKRFoldInstance<F> {
+    /// Commits to f2 and f3 with a multilinear polynomial commitment scheme, so that the claims
+    /// `f2(u)` and `f3(v)` of `verify_round_claim` can be discharged by opening the commitments.
+    pub fn commit<P: MultilinearPCS<F>>(
+        &self,
+        pp: &P::Parameters,
+    ) -> Result<(P::Commitment, P::Commitment), crate::Error> {
+        Ok((P::commit(pp, &self.f2)?, P::commit(pp, &self.f3)?))
+    }
+
+    /// Opens f2 at `u` and f3 at `v`, see `verify_round_claim_openings`.
+    pub fn open_round_claim<P: MultilinearPCS<F>>(
+        &self,
+        pp: &P::Parameters,
+        u: &[F],
+        v: &[F],
+    ) -> Result<(P::Proof, P::Proof), crate::Error> {
+        Ok((P::open(pp, &self.f2, u)?, P::open(pp, &self.f3, v)?))
+    }
+}
+
 /// The `gkrfold` function constructs a SumFoldProof from a vector of GKRFold instances.
 /// The SumFoldProof contains a Q polynomial, a vector of f_j polynomials, and a SumFoldInstance.
 #[allow(non_snake_case)]
@@ -84,3 +107,39 @@ pub fn gkrfold<F: Field, R: Rng>(
 
     SumFoldProof::sumfold(sc_instances, sumfold_rng)
 }
+
+/// Verifies the claim `f1(g,u,v) * f2(u) * f3(v) = expected` on the GKR round function of an
+/// instance, given the claimed evaluations `f2_u = f2(u)` and `f3_v = f3(v)`.
+///
+/// `wiring` may be the `f1` of the instance, or a `WiringPredicate` that evaluates the same
+/// predicate in time polylogarithmic in the size of the circuit.
+pub fn verify_round_claim<F: Field, W: WiringPredicate<F>>(
+    wiring: &W,
+    g: &[F],
+    u: &[F],
+    v: &[F],
+    f2_u: F,
+    f3_v: F,
+    expected: F,
+) -> bool {
+    wiring.num_vars() == g.len() + u.len() + v.len()
+        && wiring.evaluate_wiring(g, u, v) * f2_u * f3_v == expected
+}
+
+/// Verifies the claimed evaluations `f2_u = f2(u)` and `f3_v = f3(v)` used by
+/// `verify_round_claim`, given the commitments of `GKRFoldInstance::commit` and the openings of
+/// `GKRFoldInstance::open_round_claim`, so that the verifier does not need to read f2 and f3.
+///
+/// Returns an error if an opening is malformed.
+pub fn verify_round_claim_openings<F: Field, P: MultilinearPCS<F>>(
+    pp: &P::Parameters,
+    (f2_commitment, f3_commitment): &(P::Commitment, P::Commitment),
+    u: &[F],
+    v: &[F],
+    f2_u: F,
+    f3_v: F,
+    (f2_opening, f3_opening): &(P::Proof, P::Proof),
+) -> Result<bool, crate::Error> {
+    Ok(P::verify(pp, f2_commitment, u, f2_u, f2_opening)?
+        && P::verify(pp, f3_commitment, v, f3_v, f3_opening)?)
+}
@@ -1,8 +1,10 @@
 use super::*;
+use crate::pcs::kzg::MultilinearKZG;
+use crate::pcs::transparent::TransparentPCS;
 use crate::sumfold::utils::build_random_poly;
 use ark_ff::UniformRand;
 use ark_std::rand::{rngs::StdRng, SeedableRng};
-use ark_test_curves::bls12_381::Fr as FF;
+use ark_test_curves::bls12_381::{Bls12_381, Fr as FF};
 
 fn random_gkr_instance<F: Field, R: Rng>(
     dim: usize,
@@ -53,3 +55,88 @@ fn test_gkrfold_correctness() {
         }
     }
 }
+
+#[test]
+fn test_verify_round_claim_with_wiring_predicate() {
+    use crate::gkr_round_sumcheck::wiring::BinaryTree;
+
+    let mut rng = StdRng::seed_from_u64(7);
+    let dim = 3;
+    let tree = BinaryTree { dim };
+    let instance = GKRFoldInstance {
+        f1: tree.gate_list().to_sparse_mle(),
+        f2: build_random_poly(dim + 1, &mut rng),
+        f3: build_random_poly(dim + 1, &mut rng),
+        g: (0..dim).map(|_| FF::rand(&mut rng)).collect(),
+    };
+    let u: Vec<_> = (0..dim + 1).map(|_| FF::rand(&mut rng)).collect();
+    let v: Vec<_> = (0..dim + 1).map(|_| FF::rand(&mut rng)).collect();
+    let (f2_u, f3_v) = (instance.f2.evaluate(&u), instance.f3.evaluate(&v));
+    let guv: Vec<_> = instance.g.iter().chain(&u).chain(&v).copied().collect();
+    let expected = instance.f1.evaluate(&guv) * f2_u * f3_v;
+
+    let g = &instance.g;
+    assert!(verify_round_claim(
+        &instance.f1,
+        g,
+        &u,
+        &v,
+        f2_u,
+        f3_v,
+        expected
+    ));
+    assert!(verify_round_claim(&tree, g, &u, &v, f2_u, f3_v, expected));
+    assert!(!verify_round_claim(
+        &tree,
+        g,
+        &u,
+        &v,
+        f2_u,
+        f3_v,
+        expected + FF::from(1u64)
+    ));
+}
+
+fn test_round_claim_openings<P: MultilinearPCS<FF>>() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let dim = 3;
+    let (f1, f2, f3) = random_gkr_instance(dim, &mut rng);
+    let instance = GKRFoldInstance {
+        f1,
+        f2,
+        f3,
+        g: (0..dim).map(|_| FF::rand(&mut rng)).collect(),
+    };
+    let u: Vec<_> = (0..dim).map(|_| FF::rand(&mut rng)).collect();
+    let v: Vec<_> = (0..dim).map(|_| FF::rand(&mut rng)).collect();
+    let (f2_u, f3_v) = (instance.f2.evaluate(&u), instance.f3.evaluate(&v));
+
+    let pp = P::setup(dim, &mut rng).unwrap();
+    let commitments = instance.commit::<P>(&pp).unwrap();
+    let openings = instance.open_round_claim::<P>(&pp, &u, &v).unwrap();
+    assert!(
+        verify_round_claim_openings::<_, P>(&pp, &commitments, &u, &v, f2_u, f3_v, &openings)
+            .unwrap()
+    );
+    let wrong = f3_v + FF::from(1u64);
+    assert!(!verify_round_claim_openings::<_, P>(
+        &pp,
+        &commitments,
+        &u,
+        &v,
+        f2_u,
+        wrong,
+        &openings
+    )
+    .unwrap());
+}
+
+#[test]
+fn test_round_claim_openings_transparent() {
+    test_round_claim_openings::<TransparentPCS<FF>>();
+}
+
+#[test]
+fn test_round_claim_openings_kzg() {
+    test_round_claim_openings::<MultilinearKZG<Bls12_381>>();
+}
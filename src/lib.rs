@@ -19,12 +19,15 @@ extern crate ark_std;
 /// error for this crate
 mod error;
 
+pub mod gkr;
 pub mod gkr_round_sumcheck;
 pub mod gkrfold;
 pub mod ml_sumcheck;
+pub mod pcs;
 pub mod sumfold;
 
 pub mod rng;
+pub mod utils;
 
 #[cfg(test)]
 mod tests {}
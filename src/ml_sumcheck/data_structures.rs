@@ -1,10 +1,13 @@
 //! Defines the data structures used by the `MLSumcheck` protocol.
 
 use ark_ff::Field;
-use ark_poly::{DenseMultilinearExtension, Polynomial};
+use ark_poly::{DenseMultilinearExtension, Polynomial, SparseMultilinearExtension};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::cmp::max;
-use ark_std::rc::Rc;
+use ark_std::fmt::Debug;
+use ark_std::marker::PhantomData;
+use ark_std::rand::RngCore;
+use ark_std::sync::Arc;
 use ark_std::vec::Vec;
 use hashbrown::HashMap;
 /// Stores a list of products of `DenseMultilinearExtension` that is meant to be added together.
@@ -20,6 +23,10 @@ use hashbrown::HashMap;
 ///
 /// $$\sum_{i=0}^{n}C_i\cdot\prod_{j=0}^{m_i}P_{ij}$$
 ///
+/// Products may also have sparse multiplicands, added with `add_product_with_sparse`. The sparse
+/// multiplicands of the ith product are `self.sparse_products[i]`, indices into
+/// `self.flattened_sparse_ml_extensions`.
+///
 /// The result polynomial is used as the prover key.
 #[derive(Clone)]
 pub struct ListOfProductsOfPolynomials<F: Field> {
@@ -30,8 +37,16 @@ pub struct ListOfProductsOfPolynomials<F: Field> {
     /// list of reference to products (as usize) of multilinear extension
     pub products: Vec<(F, Vec<usize>)>,
     /// Stores multilinear extensions in which product multiplicand can refer to.
-    pub flattened_ml_extensions: Vec<Rc<DenseMultilinearExtension<F>>>,
-    raw_pointers_lookup_table: HashMap<*const DenseMultilinearExtension<F>, usize>,
+    pub flattened_ml_extensions: Vec<Arc<DenseMultilinearExtension<F>>>,
+    /// Maps the address of each multiplicand to its index in `flattened_ml_extensions`. Addresses
+    /// are stored as `usize` rather than raw pointers, so that the polynomial is `Send` and `Sync`.
+    raw_pointers_lookup_table: HashMap<usize, usize>,
+    /// list of references to the sparse multiplicands of each product, empty for products of dense
+    /// multilinear extensions only
+    pub sparse_products: Vec<Vec<usize>>,
+    /// Stores sparse multilinear extensions in which `sparse_products` can refer to.
+    pub flattened_sparse_ml_extensions: Vec<Arc<SparseMultilinearExtension<F>>>,
+    sparse_pointers_lookup_table: HashMap<usize, usize>,
 }
 
 impl<F: Field> ListOfProductsOfPolynomials<F> {
@@ -63,6 +78,9 @@ impl<F: Field> ListOfProductsOfPolynomials<F> {
             products: Vec::new(),
             flattened_ml_extensions: Vec::new(),
             raw_pointers_lookup_table: HashMap::new(),
+            sparse_products: Vec::new(),
+            flattened_sparse_ml_extensions: Vec::new(),
+            sparse_pointers_lookup_table: HashMap::new(),
         }
     }
 
@@ -70,41 +88,252 @@ impl<F: Field> ListOfProductsOfPolynomials<F> {
     /// The resulting polynomial will be multiplied by the scalar `coefficient`.
     pub fn add_product(
         &mut self,
-        product: impl IntoIterator<Item = Rc<DenseMultilinearExtension<F>>>,
+        product: impl IntoIterator<Item = Arc<DenseMultilinearExtension<F>>>,
         coefficient: F,
     ) {
-        let product: Vec<Rc<DenseMultilinearExtension<F>>> = product.into_iter().collect();
-        let mut indexed_product = Vec::with_capacity(product.len());
-        assert!(!product.is_empty());
-        self.max_multiplicands = max(self.max_multiplicands, product.len());
-        for m in product {
-            assert_eq!(
-                m.num_vars, self.num_variables,
-                "product has a multiplicand with wrong number of variables"
-            );
-            let m_ptr: *const DenseMultilinearExtension<F> = Rc::as_ptr(&m);
-            if let Some(index) = self.raw_pointers_lookup_table.get(&m_ptr) {
-                indexed_product.push(*index)
-            } else {
-                let curr_index = self.flattened_ml_extensions.len();
-                self.flattened_ml_extensions.push(m.clone());
-                self.raw_pointers_lookup_table.insert(m_ptr, curr_index);
-                indexed_product.push(curr_index);
-            }
-        }
+        self.add_product_with_sparse(Vec::new(), product, coefficient)
+    }
+
+    /// Add a list of sparse and dense multilinear extensions that is meant to be multiplied
+    /// together. The resulting polynomial will be multiplied by the scalar `coefficient`.
+    ///
+    /// The prover only visits the nonzero entries of the sparse multiplicands for this product.
+    pub fn add_product_with_sparse(
+        &mut self,
+        sparse: impl IntoIterator<Item = Arc<SparseMultilinearExtension<F>>>,
+        dense: impl IntoIterator<Item = Arc<DenseMultilinearExtension<F>>>,
+        coefficient: F,
+    ) {
+        let num_variables = self.num_variables;
+        let sparse: Vec<_> = sparse.into_iter().collect();
+        let dense: Vec<_> = dense.into_iter().collect();
+        assert!(!sparse.is_empty() || !dense.is_empty());
+        self.max_multiplicands = max(self.max_multiplicands, sparse.len() + dense.len());
+        let indexed_product = dense
+            .into_iter()
+            .map(|m| {
+                assert_eq!(
+                    m.num_vars, num_variables,
+                    "product has a multiplicand with wrong number of variables"
+                );
+                lookup_or_insert(
+                    &mut self.flattened_ml_extensions,
+                    &mut self.raw_pointers_lookup_table,
+                    m,
+                )
+            })
+            .collect();
+        let indexed_sparse_product = sparse
+            .into_iter()
+            .map(|m| {
+                assert_eq!(
+                    m.num_vars, num_variables,
+                    "product has a multiplicand with wrong number of variables"
+                );
+                lookup_or_insert(
+                    &mut self.flattened_sparse_ml_extensions,
+                    &mut self.sparse_pointers_lookup_table,
+                    m,
+                )
+            })
+            .collect();
         self.products.push((coefficient, indexed_product));
+        self.sparse_products.push(indexed_sparse_product);
     }
 
     /// Evaluate the polynomial at point `point`
     pub fn evaluate(&self, point: &[F]) -> F {
+        let point = point.to_vec();
         self.products
             .iter()
-            .map(|(c, p)| {
-                *c * p
+            .zip(&self.sparse_products)
+            .map(|((c, p), s)| {
+                let dense = p
+                    .iter()
+                    .map(|&i| self.flattened_ml_extensions[i].evaluate(&point));
+                let sparse = s
                     .iter()
-                    .map(|&i| self.flattened_ml_extensions[i].evaluate(&point.to_vec()))
-                    .product::<F>()
+                    .map(|&i| self.flattened_sparse_ml_extensions[i].evaluate(&point));
+                *c * dense.chain(sparse).product::<F>()
             })
             .sum()
     }
 }
+
+/// Returns the index of `m` in `flattened`, and stores it there if it is a new multiplicand.
+/// Multiplicands are identified by their address, so that shared multiplicands are stored once.
+fn lookup_or_insert<T>(
+    flattened: &mut Vec<Arc<T>>,
+    lookup_table: &mut HashMap<usize, usize>,
+    m: Arc<T>,
+) -> usize {
+    let m_ptr = Arc::as_ptr(&m) as usize;
+    *lookup_table.entry(m_ptr).or_insert_with(|| {
+        flattened.push(m);
+        flattened.len() - 1
+    })
+}
+
+/// Polynomial `g(x) = a_0 + g_1(x_1) + ... + g_n(x_n)`, where each `g_i` is a univariate polynomial
+/// of small degree without constant term.
+///
+/// Used to mask the prover messages of a zero-knowledge sumcheck, as described in
+/// [XZZPS19](https://eprint.iacr.org/2019/317.pdf#subsection.4.1) (Section 4.1).
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct SumOfUnivariates<F: Field> {
+    /// the constant term `a_0`
+    pub constant: F,
+    /// `coefficients[i][e]` is the coefficient of `x_i^(e + 1)` in `g_i`
+    pub coefficients: Vec<Vec<F>>,
+}
+
+impl<F: Field> SumOfUnivariates<F> {
+    /// Samples a random polynomial in `num_vars` variables, of degree `degree` in each variable.
+    pub fn rand<R: RngCore>(num_vars: usize, degree: usize, rng: &mut R) -> Self {
+        Self {
+            constant: F::rand(rng),
+            coefficients: (0..num_vars)
+                .map(|_| (0..degree).map(|_| F::rand(rng)).collect())
+                .collect(),
+        }
+    }
+
+    /// Returns the number of variables.
+    pub fn num_vars(&self) -> usize {
+        self.coefficients.len()
+    }
+
+    /// Evaluates `g_i` at `x`.
+    fn univariate(&self, i: usize, x: F) -> F {
+        self.coefficients[i]
+            .iter()
+            .rev()
+            .fold(F::zero(), |acc, c| (acc + c) * x)
+    }
+
+    /// Evaluates the polynomial at `point`.
+    pub fn evaluate(&self, point: &[F]) -> F {
+        assert_eq!(point.len(), self.num_vars());
+        self.constant
+            + point
+                .iter()
+                .enumerate()
+                .map(|(i, x)| self.univariate(i, *x))
+                .sum::<F>()
+    }
+
+    /// Returns the sum of the polynomial over the boolean hypercube.
+    pub fn sum_over_hypercube(&self) -> F {
+        self.round_evaluations(&[], 2).iter().copied().sum()
+    }
+
+    /// Returns the sumcheck message of the polynomial at round `fixed.len() + 1`, i.e. the
+    /// evaluations at `t = 0, ..., num_evaluations - 1` of the sum of `g(fixed, t, b)` over the
+    /// boolean `b`.
+    pub fn round_evaluations(&self, fixed: &[F], num_evaluations: usize) -> Vec<F> {
+        let (nv, k) = (self.num_vars(), fixed.len());
+        assert!(k < nv);
+        let two = F::from(2u64);
+        // each g_j with j > k is 0 at b_j = 0, and g_j(1) on half of the points
+        let count = two.pow([(nv - k - 1) as u64]);
+        let rest: F = (k + 1..nv).map(|j| self.univariate(j, F::one())).sum();
+        let rest = if k + 1 < nv {
+            rest * two.pow([(nv - k - 2) as u64])
+        } else {
+            F::zero()
+        };
+        let base = self.constant
+            + fixed
+                .iter()
+                .enumerate()
+                .map(|(j, r)| self.univariate(j, *r))
+                .sum::<F>();
+        (0..num_evaluations)
+            .map(|t| count * (base + self.univariate(k, F::from(t as u64))) + rest)
+            .collect()
+    }
+}
+
+/// Commitment scheme for the masking polynomials of the zero-knowledge sumchecks.
+///
+/// The masks are sums of univariate polynomials, which are not multilinear, so they have their own
+/// scheme. The prover commits to a mask before it is used, and the scheme must be hiding for the
+/// protocols to be zero-knowledge.
+pub trait MaskCommitmentScheme<F: Field> {
+    /// public parameters
+    type Parameters: Clone + Debug;
+    /// commitment to a mask
+    type Commitment: Clone + Debug + PartialEq + CanonicalSerialize + CanonicalDeserialize;
+    /// proof of an evaluation of a mask
+    type Proof: Clone + Debug + CanonicalSerialize + CanonicalDeserialize;
+
+    /// Commits to `mask`.
+    fn commit(
+        pp: &Self::Parameters,
+        mask: &SumOfUnivariates<F>,
+    ) -> Result<Self::Commitment, crate::Error>;
+
+    /// Proves the evaluation of `mask` at `point`.
+    fn open(
+        pp: &Self::Parameters,
+        mask: &SumOfUnivariates<F>,
+        point: &[F],
+    ) -> Result<Self::Proof, crate::Error>;
+
+    /// Checks that the committed mask evaluates to `value` at `point`.
+    fn verify(
+        pp: &Self::Parameters,
+        commitment: &Self::Commitment,
+        point: &[F],
+        value: F,
+        proof: &Self::Proof,
+    ) -> Result<bool, crate::Error>;
+}
+
+/// Mask "commitment" that reveals the mask, for tests and debugging.
+///
+/// It is not hiding, so the protocols are not zero-knowledge when it is used.
+pub struct TransparentMaskCommitment<F: Field> {
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> MaskCommitmentScheme<F> for TransparentMaskCommitment<F> {
+    type Parameters = ();
+    type Commitment = SumOfUnivariates<F>;
+    type Proof = ();
+
+    fn commit(
+        _pp: &Self::Parameters,
+        mask: &SumOfUnivariates<F>,
+    ) -> Result<Self::Commitment, crate::Error> {
+        Ok(mask.clone())
+    }
+
+    fn open(
+        _pp: &Self::Parameters,
+        mask: &SumOfUnivariates<F>,
+        point: &[F],
+    ) -> Result<Self::Proof, crate::Error> {
+        check_mask_point(mask, point)
+    }
+
+    fn verify(
+        _pp: &Self::Parameters,
+        commitment: &Self::Commitment,
+        point: &[F],
+        value: F,
+        _proof: &Self::Proof,
+    ) -> Result<bool, crate::Error> {
+        check_mask_point(commitment, point)?;
+        Ok(commitment.evaluate(point) == value)
+    }
+}
+
+fn check_mask_point<F: Field>(mask: &SumOfUnivariates<F>, point: &[F]) -> Result<(), crate::Error> {
+    if point.len() != mask.num_vars() {
+        return Err(crate::Error::OtherError(
+            "Point does not match the number of variables of the mask.".into(),
+        ));
+    }
+    Ok(())
+}
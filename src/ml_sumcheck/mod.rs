@@ -1,12 +1,19 @@
 //! Sumcheck Protocol for multilinear extension
 
-use crate::ml_sumcheck::data_structures::{ListOfProductsOfPolynomials, PolynomialInfo};
-use crate::ml_sumcheck::protocol::prover::{ProverMsg, ProverState};
-use crate::ml_sumcheck::protocol::verifier::SubClaim;
+use crate::ml_sumcheck::data_structures::{
+    ListOfProductsOfPolynomials, MaskCommitmentScheme, PolynomialInfo, SumOfUnivariates,
+};
+use crate::ml_sumcheck::protocol::prover::{CompressedProverMsg, ProverMsg, ProverState};
+use crate::ml_sumcheck::protocol::verifier::{
+    interpolate_uni_poly, SubClaim, VerifierMsg, ZKSubClaim,
+};
+use crate::ml_sumcheck::protocol::zerocheck::ZerocheckProverState;
 use crate::ml_sumcheck::protocol::IPForMLSumcheck;
 use crate::rng::{Blake2b512Rng, FeedableRNG};
 use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::marker::PhantomData;
+use ark_std::rand::RngCore;
 use ark_std::vec::Vec;
 
 pub mod protocol;
@@ -21,6 +28,73 @@ pub struct MLSumcheck<F: Field>(#[doc(hidden)] PhantomData<F>);
 /// proof generated by prover
 pub type Proof<F> = Vec<ProverMsg<F>>;
 
+/// proof without `P(1)` in each round, one field element per round smaller than `Proof`
+pub type CompressedProof<F> = Vec<CompressedProverMsg<F>>;
+
+/// proof generated by the prover of the zero-knowledge sumcheck
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ZKProof<F: Field> {
+    /// sum of the masking polynomial over the boolean hypercube
+    pub mask_sum: F,
+    /// masked prover messages
+    pub msgs: Vec<ProverMsg<F>>,
+    /// evaluation of the masking polynomial at the point of the subclaim
+    pub mask_evaluation: F,
+}
+
+/// zero-knowledge proof along with the commitment to the masking polynomial and its opening at the
+/// point of the subclaim
+pub type CommittedZKProof<F, M> = (
+    ZKProof<F>,
+    <M as MaskCommitmentScheme<F>>::Commitment,
+    <M as MaskCommitmentScheme<F>>::Proof,
+);
+
+/// proof of several sums generated by `MLSumcheck::prove_batch`
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct BatchProof<F: Field> {
+    /// sumcheck of the combined claim
+    pub sumcheck_proof: Proof<F>,
+    /// evaluation of each polynomial at its suffix of the point of the sumcheck
+    pub evaluations: Vec<F>,
+}
+
+/// State of the prover after `MLSumcheck::prove_batch_as_subprotocol`
+#[derive(Clone)]
+pub struct BatchProverState<F: Field> {
+    /// sum of each polynomial
+    pub claimed_sums: Vec<F>,
+    /// point of the sumcheck, whose suffix of length `n_i` is the point of the ith polynomial
+    pub randomness: Vec<F>,
+}
+
+/// Feeds the claims of a batch to the transcript, and returns the coefficients of the combination
+/// along with the number of variables and degree of the combined sumcheck.
+fn batch_coefficients<F: Field>(
+    fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+    polynomial_infos: &[PolynomialInfo],
+    claimed_sums: &[F],
+) -> Result<(Vec<F>, PolynomialInfo), crate::Error> {
+    for info in polynomial_infos {
+        fs_rng.feed(info)?;
+    }
+    fs_rng.feed(&claimed_sums.to_vec())?;
+    let coefficients = (0..claimed_sums.len()).map(|_| F::rand(fs_rng)).collect();
+    let info = PolynomialInfo {
+        max_multiplicands: polynomial_infos
+            .iter()
+            .map(|info| info.max_multiplicands)
+            .max()
+            .unwrap_or(0),
+        num_variables: polynomial_infos
+            .iter()
+            .map(|info| info.num_variables)
+            .max()
+            .unwrap_or(0),
+    };
+    Ok((coefficients, info))
+}
+
 impl<F: Field> MLSumcheck<F> {
     /// extract sum from the proof
     pub fn extract_sum(proof: &Proof<F>) -> F {
@@ -87,15 +161,497 @@ impl<F: Field> MLSumcheck<F> {
         claimed_sum: F,
         proof: &Proof<F>,
     ) -> Result<SubClaim<F>, crate::Error> {
+        if proof.len() != polynomial_info.num_variables {
+            return Err(crate::Error::Reject(Some(format!(
+                "Proof has {} messages, expected {}.",
+                proof.len(),
+                polynomial_info.num_variables
+            ))));
+        }
+        fs_rng.feed(polynomial_info)?;
+        let mut verifier_state =
+            IPForMLSumcheck::verifier_init_with_claim(polynomial_info, claimed_sum);
+        for prover_msg in proof {
+            fs_rng.feed(prover_msg)?;
+            IPForMLSumcheck::verify_round_with_claim(
+                (*prover_msg).clone(),
+                &mut verifier_state,
+                fs_rng,
+            )?;
+        }
+
+        IPForMLSumcheck::generate_subclaim(verifier_state)
+    }
+
+    /// generate one proof of the sums of several polynomials, each over {0,1}^`num_vars` of its own
+    ///
+    /// The claims `sum_x f_i(x) = v_i` are combined with coefficients `c_i` derived from the
+    /// transcript. With `n` the largest number of variables, a polynomial `f_i` in `n_i` variables
+    /// is seen as a polynomial in `n` variables that only depends on the last `n_i`, so that the
+    /// combined claim is `sum_i c_i 2^(n - n_i) v_i`. The prover of `f_i` only starts in round
+    /// `n - n_i + 1`, and contributes a constant before.
+    ///
+    /// Returns the proof along with the sum of each polynomial.
+    pub fn prove_batch(
+        polynomials: &[ListOfProductsOfPolynomials<F>],
+    ) -> Result<(BatchProof<F>, Vec<F>), crate::Error> {
+        let mut fs_rng = Blake2b512Rng::setup();
+        Self::prove_batch_as_subprotocol(&mut fs_rng, polynomials)
+            .map(|(proof, state)| (proof, state.claimed_sums))
+    }
+
+    /// This function does the same thing as `prove_batch`, but it uses a `FeedableRNG` as the
+    /// transcript. Additionally, it returns the prover's state, with the point of the sumcheck.
+    pub fn prove_batch_as_subprotocol(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        polynomials: &[ListOfProductsOfPolynomials<F>],
+    ) -> Result<(BatchProof<F>, BatchProverState<F>), crate::Error> {
+        if polynomials.is_empty() {
+            return Err(crate::Error::OtherError(
+                "There must be at least one polynomial.".into(),
+            ));
+        }
+        // the first message of each prover is computed ahead, as it gives the sum
+        let mut prover_states = Vec::with_capacity(polynomials.len());
+        let mut first_msgs = Vec::with_capacity(polynomials.len());
+        let mut claimed_sums = Vec::with_capacity(polynomials.len());
+        for polynomial in polynomials {
+            if polynomial.num_variables == 0 {
+                prover_states.push(None);
+                first_msgs.push(None);
+                claimed_sums.push(polynomial.evaluate(&[]));
+            } else {
+                let mut prover_state = IPForMLSumcheck::prover_init(polynomial);
+                let msg = IPForMLSumcheck::prove_round(&mut prover_state, &None);
+                claimed_sums.push(msg.evaluations[0] + msg.evaluations[1]);
+                prover_states.push(Some(prover_state));
+                first_msgs.push(Some(msg));
+            }
+        }
+        let infos: Vec<_> = polynomials.iter().map(|p| p.info()).collect();
+        let (coefficients, info) = batch_coefficients(fs_rng, &infos, &claimed_sums)?;
+        let (nv, degree) = (info.num_variables, info.max_multiplicands);
+        fs_rng.feed(&info)?;
+
+        let two = F::from(2u64);
+        let mut point = Vec::with_capacity(nv);
+        let mut sumcheck_proof = Vec::with_capacity(nv);
+        for round in 1..=nv {
+            let mut evaluations = vec![F::zero(); degree + 1];
+            for i in 0..polynomials.len() {
+                let start = nv - infos[i].num_variables + 1;
+                let msg = if round < start {
+                    // f_i does not depend on this variable nor on the next `start - round - 1`
+                    let constant =
+                        coefficients[i] * claimed_sums[i] * two.pow([(start - round - 1) as u64]);
+                    evaluations.iter_mut().for_each(|e| *e += constant);
+                    continue;
+                } else if round == start {
+                    first_msgs[i].take().unwrap()
+                } else {
+                    let v_msg = Some(VerifierMsg {
+                        randomness: point[round - 2],
+                    });
+                    IPForMLSumcheck::prove_round(prover_states[i].as_mut().unwrap(), &v_msg)
+                };
+                for (t, e) in evaluations.iter_mut().enumerate() {
+                    let value = msg.evaluations.get(t).copied().unwrap_or_else(|| {
+                        interpolate_uni_poly(&msg.evaluations, F::from(t as u64))
+                    });
+                    *e += coefficients[i] * value;
+                }
+            }
+            let prover_msg = ProverMsg { evaluations };
+            fs_rng.feed(&prover_msg)?;
+            sumcheck_proof.push(prover_msg);
+            point.push(IPForMLSumcheck::sample_round(fs_rng).randomness);
+        }
+
+        let evaluations: Vec<_> = polynomials
+            .iter()
+            .map(|p| p.evaluate(&point[nv - p.num_variables..]))
+            .collect();
+        fs_rng.feed(&evaluations)?;
+        Ok((
+            BatchProof {
+                sumcheck_proof,
+                evaluations,
+            },
+            BatchProverState {
+                claimed_sums,
+                randomness: point,
+            },
+        ))
+    }
+
+    /// verify the claimed sums of several polynomials using the proof of `prove_batch`
+    ///
+    /// Returns one subclaim per polynomial, whose point is the suffix of the point of the sumcheck
+    /// of length `n_i`. If the claimed sums are correct, then each polynomial evaluated at the point
+    /// of its subclaim is the expected evaluation.
+    pub fn verify_batch(
+        polynomial_infos: &[PolynomialInfo],
+        claimed_sums: &[F],
+        proof: &BatchProof<F>,
+    ) -> Result<Vec<SubClaim<F>>, crate::Error> {
+        let mut fs_rng = Blake2b512Rng::setup();
+        Self::verify_batch_as_subprotocol(&mut fs_rng, polynomial_infos, claimed_sums, proof)
+    }
+
+    /// This function does the same thing as `verify_batch`, but it uses a `FeedableRNG` as the
+    /// transcript.
+    pub fn verify_batch_as_subprotocol(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        polynomial_infos: &[PolynomialInfo],
+        claimed_sums: &[F],
+        proof: &BatchProof<F>,
+    ) -> Result<Vec<SubClaim<F>>, crate::Error> {
+        if polynomial_infos.is_empty() || polynomial_infos.len() != claimed_sums.len() {
+            return Err(crate::Error::OtherError(
+                "There must be one claimed sum per polynomial.".into(),
+            ));
+        }
+        if proof.evaluations.len() != claimed_sums.len() {
+            return Err(crate::Error::Reject(Some(format!(
+                "Proof has {} evaluations, expected {}.",
+                proof.evaluations.len(),
+                claimed_sums.len()
+            ))));
+        }
+        let (coefficients, info) = batch_coefficients(fs_rng, polynomial_infos, claimed_sums)?;
+        let nv = info.num_variables;
+        let two = F::from(2u64);
+        let combined_sum = polynomial_infos
+            .iter()
+            .zip(&coefficients)
+            .zip(claimed_sums)
+            .map(|((info, c), v)| *c * v * two.pow([(nv - info.num_variables) as u64]))
+            .sum();
+
+        let subclaim =
+            Self::verify_as_subprotocol(fs_rng, &info, combined_sum, &proof.sumcheck_proof)?;
+        let combined_evaluation: F = coefficients
+            .iter()
+            .zip(&proof.evaluations)
+            .map(|(c, y)| *c * y)
+            .sum();
+        if combined_evaluation != subclaim.expected_evaluation {
+            return Err(crate::Error::Reject(Some(
+                "Evaluations are not consistent with the sumcheck.".into(),
+            )));
+        }
+        fs_rng.feed(&proof.evaluations)?;
+
+        Ok(polynomial_infos
+            .iter()
+            .zip(&proof.evaluations)
+            .map(|(info, y)| SubClaim {
+                point: subclaim.point[nv - info.num_variables..].to_vec(),
+                expected_evaluation: *y,
+            })
+            .collect())
+    }
+
+    /// compress the proof by dropping `P(1)` from each round
+    pub fn compress_proof(proof: &Proof<F>) -> CompressedProof<F> {
+        proof.iter().map(|msg| msg.compress()).collect()
+    }
+
+    /// verify the claimed sum using a compressed proof
+    pub fn verify_compressed(
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        proof: &CompressedProof<F>,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        let mut fs_rng = Blake2b512Rng::setup();
+        Self::verify_compressed_as_subprotocol(&mut fs_rng, polynomial_info, claimed_sum, proof)
+    }
+
+    /// This function does the same thing as `verify_compressed`, but it uses a `FeedableRNG` as
+    /// the transcript. The transcript is the same as for the uncompressed proof, as the verifier
+    /// feeds the recovered messages.
+    pub fn verify_compressed_as_subprotocol(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        proof: &CompressedProof<F>,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        if proof.len() != polynomial_info.num_variables {
+            return Err(crate::Error::Reject(Some(format!(
+                "Proof has {} messages, expected {}.",
+                proof.len(),
+                polynomial_info.num_variables
+            ))));
+        }
+        fs_rng.feed(polynomial_info)?;
+        let mut verifier_state =
+            IPForMLSumcheck::verifier_init_with_claim(polynomial_info, claimed_sum);
+        for compressed_msg in proof {
+            let prover_msg = IPForMLSumcheck::decompress_round(compressed_msg, &verifier_state)?;
+            fs_rng.feed(&prover_msg)?;
+            IPForMLSumcheck::verify_round_with_claim(prover_msg, &mut verifier_state, fs_rng)?;
+        }
+
+        IPForMLSumcheck::generate_subclaim(verifier_state)
+    }
+
+    /// generate proof that the polynomial is zero on {0,1}^`num_vars`
+    ///
+    /// The verifier samples `τ` from the transcript, and the prover runs the sumcheck of
+    /// `sum_x eq(τ, x) f(x) = 0` with `eq(τ, ·)` factored out of the round polynomials, as
+    /// described in `protocol::zerocheck`. Each message has `max_multiplicands + 1` evaluations,
+    /// instead of `max_multiplicands + 2` for the sumcheck of `eq(τ, ·) f`.
+    pub fn prove_zerocheck(
+        polynomial: &ListOfProductsOfPolynomials<F>,
+    ) -> Result<Proof<F>, crate::Error> {
+        let mut fs_rng = Blake2b512Rng::setup();
+        Self::prove_zerocheck_as_subprotocol(&mut fs_rng, polynomial).map(|r| r.0)
+    }
+
+    /// This function does the same thing as `prove_zerocheck`, but it uses a `FeedableRNG` as the
+    /// transcript. Additionally, it returns the prover's state.
+    pub fn prove_zerocheck_as_subprotocol(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        polynomial: &ListOfProductsOfPolynomials<F>,
+    ) -> Result<(Proof<F>, ZerocheckProverState<F>), crate::Error> {
+        fs_rng.feed(&polynomial.info())?;
+        let tau: Vec<_> = (0..polynomial.num_variables)
+            .map(|_| F::rand(fs_rng))
+            .collect();
+
+        let mut prover_state = IPForMLSumcheck::zerocheck_prover_init(polynomial, &tau);
+        let mut verifier_msg = None;
+        let mut prover_msgs = Vec::with_capacity(polynomial.num_variables);
+        for _ in 0..polynomial.num_variables {
+            let prover_msg =
+                IPForMLSumcheck::prove_zerocheck_round(&mut prover_state, &verifier_msg);
+            fs_rng.feed(&prover_msg)?;
+            prover_msgs.push(prover_msg);
+            verifier_msg = Some(IPForMLSumcheck::sample_round(fs_rng));
+        }
+        prover_state
+            .sumcheck_state
+            .randomness
+            .push(verifier_msg.unwrap().randomness);
+
+        Ok((prover_msgs, prover_state))
+    }
+
+    /// verify that the polynomial is zero on {0,1}^`num_vars` using the proof
+    ///
+    /// If it is, then the polynomial evaluated at `subclaim.point` is
+    /// `subclaim.expected_evaluation`.
+    pub fn verify_zerocheck(
+        polynomial_info: &PolynomialInfo,
+        proof: &Proof<F>,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        let mut fs_rng = Blake2b512Rng::setup();
+        Self::verify_zerocheck_as_subprotocol(&mut fs_rng, polynomial_info, proof)
+    }
+
+    /// This function does the same thing as `verify_zerocheck`, but it uses a `FeedableRNG` as the
+    /// transcript.
+    pub fn verify_zerocheck_as_subprotocol(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        polynomial_info: &PolynomialInfo,
+        proof: &Proof<F>,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        if proof.len() != polynomial_info.num_variables {
+            return Err(crate::Error::Reject(Some(format!(
+                "Proof has {} messages, expected {}.",
+                proof.len(),
+                polynomial_info.num_variables
+            ))));
+        }
         fs_rng.feed(polynomial_info)?;
+        let tau = (0..polynomial_info.num_variables)
+            .map(|_| F::rand(fs_rng))
+            .collect();
+
+        let mut verifier_state = IPForMLSumcheck::zerocheck_verifier_init(polynomial_info, tau);
+        for prover_msg in proof {
+            fs_rng.feed(prover_msg)?;
+            IPForMLSumcheck::verify_zerocheck_round(
+                prover_msg.clone(),
+                &mut verifier_state,
+                fs_rng,
+            )?;
+        }
+
+        IPForMLSumcheck::zerocheck_subclaim(verifier_state)
+    }
+
+    /// generate a zero-knowledge proof of the sum of polynomial over {0,1}^`num_vars`
+    ///
+    /// The prover samples a masking polynomial `G(x) = a_0 + g_1(x_1) + ... + g_n(x_n)` of the same
+    /// degree as the polynomial with `mask_rng`, commits to it with `M`, and discloses its sum. The
+    /// verifier samples `rho` from the transcript, and the sumcheck is run on `f + rho * G`, as
+    /// described in [XZZPS19](https://eprint.iacr.org/2019/317.pdf#subsection.4.1) (Section 4.1).
+    ///
+    /// Returns the proof along with the commitment to the masking polynomial and its opening at
+    /// the point of the subclaim.
+    pub fn prove_zk<M: MaskCommitmentScheme<F>, R: RngCore>(
+        pp: &M::Parameters,
+        polynomial: &ListOfProductsOfPolynomials<F>,
+        mask_rng: &mut R,
+    ) -> Result<CommittedZKProof<F, M>, crate::Error> {
+        let mask = SumOfUnivariates::rand(
+            polynomial.num_variables,
+            polynomial.max_multiplicands,
+            mask_rng,
+        );
+        let commitment = M::commit(pp, &mask)?;
+        let mut fs_rng = Blake2b512Rng::setup();
+        let (proof, prover_state) =
+            Self::prove_zk_as_subprotocol::<M>(&mut fs_rng, polynomial, &mask, &commitment)?;
+        let opening = M::open(pp, &mask, &prover_state.randomness)?;
+        Ok((proof, commitment, opening))
+    }
+
+    /// This function does the same thing as `prove_zk`, but it uses a `FeedableRNG` as the
+    /// transcript and takes the masking polynomial along with its commitment. The mask should have
+    /// the same number of variables as the polynomial and a degree of at least
+    /// `max_multiplicands`. Additionally, it returns the prover's state of the unmasked polynomial,
+    /// whose randomness is the point at which the mask must be opened.
+    pub fn prove_zk_as_subprotocol<M: MaskCommitmentScheme<F>>(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        polynomial: &ListOfProductsOfPolynomials<F>,
+        mask: &SumOfUnivariates<F>,
+        commitment: &M::Commitment,
+    ) -> Result<(ZKProof<F>, ProverState<F>), crate::Error> {
+        let nv = polynomial.num_variables;
+        let degree = polynomial.max_multiplicands;
+        if nv == 0 {
+            return Err(crate::Error::OtherError(
+                "Attempt to prove a constant.".into(),
+            ));
+        }
+        if mask.num_vars() != nv {
+            return Err(crate::Error::OtherError(
+                "Mask does not match the number of variables of the polynomial.".into(),
+            ));
+        }
+        let mask_sum = mask.sum_over_hypercube();
+        fs_rng.feed(&polynomial.info())?;
+        fs_rng.feed(commitment)?;
+        fs_rng.feed(&mask_sum)?;
+        let rho = F::rand(fs_rng);
+
+        let mut prover_state = IPForMLSumcheck::prover_init(polynomial);
+        let mut verifier_msg = None;
+        let mut msgs = Vec::with_capacity(nv);
+        for _ in 0..nv {
+            let mut prover_msg = IPForMLSumcheck::prove_round(&mut prover_state, &verifier_msg);
+            // the randomness of the previous rounds is pushed to the state by `prove_round`
+            let mask_evaluations = mask.round_evaluations(&prover_state.randomness, degree + 1);
+            for (e, m) in prover_msg.evaluations.iter_mut().zip(mask_evaluations) {
+                *e += rho * m;
+            }
+            fs_rng.feed(&prover_msg)?;
+            msgs.push(prover_msg);
+            verifier_msg = Some(IPForMLSumcheck::sample_round(fs_rng));
+        }
+        prover_state
+            .randomness
+            .push(verifier_msg.unwrap().randomness);
+
+        let mask_evaluation = mask.evaluate(&prover_state.randomness);
+        fs_rng.feed(&mask_evaluation)?;
+        Ok((
+            ZKProof {
+                mask_sum,
+                msgs,
+                mask_evaluation,
+            },
+            prover_state,
+        ))
+    }
+
+    /// verify the claimed sum using the zero-knowledge proof, the commitment to the masking
+    /// polynomial and its opening
+    ///
+    /// The claimed evaluation of the masking polynomial is checked against its commitment, and the
+    /// subclaim holds the expected evaluation of the polynomial.
+    pub fn verify_zk<M: MaskCommitmentScheme<F>>(
+        pp: &M::Parameters,
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        commitment: &M::Commitment,
+        proof: &ZKProof<F>,
+        opening: &M::Proof,
+    ) -> Result<ZKSubClaim<F>, crate::Error> {
+        let mut fs_rng = Blake2b512Rng::setup();
+        Self::verify_zk_as_subprotocol::<M>(
+            &mut fs_rng,
+            pp,
+            polynomial_info,
+            claimed_sum,
+            commitment,
+            proof,
+            opening,
+        )
+    }
+
+    /// This function does the same thing as `verify_zk`, but it uses a `FeedableRNG` as the
+    /// transcript.
+    pub fn verify_zk_as_subprotocol<M: MaskCommitmentScheme<F>>(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        pp: &M::Parameters,
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        commitment: &M::Commitment,
+        proof: &ZKProof<F>,
+        opening: &M::Proof,
+    ) -> Result<ZKSubClaim<F>, crate::Error> {
+        if polynomial_info.num_variables == 0 {
+            return Err(crate::Error::OtherError(
+                "Attempt to verify a constant.".into(),
+            ));
+        }
+        if proof.msgs.len() != polynomial_info.num_variables {
+            return Err(crate::Error::Reject(Some(
+                "Proof has the wrong number of rounds.".into(),
+            )));
+        }
+        if proof.msgs.iter().any(|msg| {
+            msg.evaluations.len() != polynomial_info.max_multiplicands + 1
+                || msg.evaluations.len() < 2
+        }) {
+            return Err(crate::Error::Reject(Some(
+                "Prover message has the wrong number of evaluations.".into(),
+            )));
+        }
+        fs_rng.feed(polynomial_info)?;
+        fs_rng.feed(commitment)?;
+        fs_rng.feed(&proof.mask_sum)?;
+        let rho = F::rand(fs_rng);
+
         let mut verifier_state = IPForMLSumcheck::verifier_init(polynomial_info);
-        for i in 0..polynomial_info.num_variables {
-            let prover_msg = proof.get(i).expect("proof is incomplete");
+        for prover_msg in &proof.msgs {
             fs_rng.feed(prover_msg)?;
-            let _verifier_msg =
-                IPForMLSumcheck::verify_round((*prover_msg).clone(), &mut verifier_state, fs_rng);
+            IPForMLSumcheck::verify_round(prover_msg.clone(), &mut verifier_state, fs_rng);
+        }
+        let subclaim = IPForMLSumcheck::check_and_generate_subclaim(
+            verifier_state,
+            claimed_sum + rho * proof.mask_sum,
+        )?;
+        fs_rng.feed(&proof.mask_evaluation)?;
+        if !M::verify(
+            pp,
+            commitment,
+            &subclaim.point,
+            proof.mask_evaluation,
+            opening,
+        )? {
+            return Err(crate::Error::Reject(Some(
+                "Masking polynomial does not match its commitment.".into(),
+            )));
         }
 
-        IPForMLSumcheck::check_and_generate_subclaim(verifier_state, claimed_sum)
+        Ok(ZKSubClaim {
+            point: subclaim.point,
+            expected_evaluation: subclaim.expected_evaluation - rho * proof.mask_evaluation,
+            mask_evaluation: proof.mask_evaluation,
+        })
     }
 }
@@ -5,6 +5,7 @@ use ark_std::marker::PhantomData;
 
 pub mod prover;
 pub mod verifier;
+pub mod zerocheck;
 pub use crate::ml_sumcheck::data_structures::{ListOfProductsOfPolynomials, PolynomialInfo};
 /// Interactive Proof for Multilinear Sumcheck
 pub struct IPForMLSumcheck<F: Field> {
@@ -3,9 +3,10 @@ use crate::ml_sumcheck::data_structures::ListOfProductsOfPolynomials;
 use crate::ml_sumcheck::protocol::verifier::VerifierMsg;
 use crate::ml_sumcheck::protocol::IPForMLSumcheck;
 use ark_ff::Field;
-use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+use ark_poly::{DenseMultilinearExtension, MultilinearExtension, SparseMultilinearExtension};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use ark_std::{cfg_iter_mut, vec::Vec};
+use ark_std::sync::Arc;
+use ark_std::{cfg_chunks, cfg_iter_mut, vec::Vec};
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
@@ -16,6 +17,37 @@ pub struct ProverMsg<F: Field> {
     pub(crate) evaluations: Vec<F>,
 }
 
+impl<F: Field> ProverMsg<F> {
+    /// Returns the evaluations of the round polynomial on 0, 1, 2, ...
+    pub fn evaluations(&self) -> &[F] {
+        &self.evaluations
+    }
+
+    /// Drops `P(1)`, which the verifier can derive from the running claim.
+    pub fn compress(&self) -> CompressedProverMsg<F> {
+        let mut evaluations = self.evaluations.clone();
+        if evaluations.len() > 1 {
+            evaluations.remove(1);
+        }
+        CompressedProverMsg { evaluations }
+    }
+}
+
+/// Prover message without `P(1)`, which the verifier derives as `claim - P(0)` from the claim of
+/// the round
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct CompressedProverMsg<F: Field> {
+    /// evaluations on P(0), P(2), P(3), ...
+    pub(crate) evaluations: Vec<F>,
+}
+
+impl<F: Field> CompressedProverMsg<F> {
+    /// Returns the evaluations of the round polynomial on 0, 2, 3, ...
+    pub fn evaluations(&self) -> &[F] {
+        &self.evaluations
+    }
+}
+
 /// Prover State
 #[derive(Clone)]
 pub struct ProverState<F: Field> {
@@ -24,8 +56,18 @@ pub struct ProverState<F: Field> {
     /// Stores the list of products that is meant to be added together. Each multiplicand is represented by
     /// the index in flattened_ml_extensions
     pub list_of_products: Vec<(F, Vec<usize>)>,
-    /// Stores a list of multilinear extensions in which `self.list_of_products` points to
-    pub flattened_ml_extensions: Vec<DenseMultilinearExtension<F>>,
+    /// Stores a list of multilinear extensions in which `self.list_of_products` points to.
+    ///
+    /// Before the first variable is fixed, these are the tables of the polynomial being proven,
+    /// shared rather than copied. Fixing a variable replaces each table by a new one of half the
+    /// size, so the tables of the polynomial are never modified.
+    pub flattened_ml_extensions: Vec<Arc<DenseMultilinearExtension<F>>>,
+    /// Stores the sparse multiplicands of each product, as indices in
+    /// `flattened_sparse_ml_extensions`
+    pub list_of_sparse_products: Vec<Vec<usize>>,
+    /// Stores a list of sparse multilinear extensions in which `self.list_of_sparse_products`
+    /// points to
+    pub flattened_sparse_ml_extensions: Vec<Arc<SparseMultilinearExtension<F>>>,
     /// Number of variables
     pub num_vars: usize,
     /// Max number of multiplicands in a product
@@ -34,6 +76,52 @@ pub struct ProverState<F: Field> {
     pub round: usize,
 }
 
+/// Fixes the first variable of `table` to `r`, writing directly into a table of half the size.
+///
+/// Unlike `MultilinearExtension::fix_variables`, this does not copy the whole table first.
+fn fix_first_variable<F: Field>(
+    table: &DenseMultilinearExtension<F>,
+    r: F,
+) -> DenseMultilinearExtension<F> {
+    let evaluations = cfg_chunks!(table.evaluations, 2)
+        .map(|pair| pair[0] + r * (pair[1] - pair[0]))
+        .collect();
+    DenseMultilinearExtension::from_evaluations_vec(table.num_vars - 1, evaluations)
+}
+
+/// Adds `scale * prod_j P_j(b, t)` to `products_sum[t]` for `t = 0, ..., degree`, where `P_j`
+/// ranges over the dense multiplicands `dense` and the sparse multiplicands `sparse` of a product,
+/// and `product` is scratch space of length `degree + 1`.
+fn add_product_at<F: Field>(
+    prover_state: &ProverState<F>,
+    scale: F,
+    dense: &[usize],
+    sparse: &[usize],
+    b: usize,
+    product: &mut [F],
+    products_sum: &mut [F],
+) {
+    let dense = dense.iter().map(|&j| {
+        let table = &prover_state.flattened_ml_extensions[j];
+        (table[b << 1], table[(b << 1) + 1])
+    });
+    let sparse = sparse.iter().map(|&j| {
+        let table = &prover_state.flattened_sparse_ml_extensions[j];
+        (table[b << 1], table[(b << 1) + 1])
+    });
+    product.fill(scale);
+    for (mut start, end) in dense.chain(sparse) {
+        let step = end - start;
+        for p in product.iter_mut() {
+            *p *= start;
+            start += step;
+        }
+    }
+    for (sum, p) in products_sum.iter_mut().zip(product.iter()) {
+        *sum += p;
+    }
+}
+
 impl<F: Field> IPForMLSumcheck<F> {
     /// initialize the prover to argue for the sum of polynomial over {0,1}^`num_vars`
     ///
@@ -53,17 +141,13 @@ impl<F: Field> IPForMLSumcheck<F> {
             panic!("Attempt to prove a constant.")
         }
 
-        // create a deep copy of all unique MLExtensions
-        let flattened_ml_extensions = polynomial
-            .flattened_ml_extensions
-            .iter()
-            .map(|x| x.as_ref().clone())
-            .collect();
-
+        // the first round only reads the tables, so they are shared with the polynomial
         ProverState {
             randomness: Vec::with_capacity(polynomial.num_variables),
             list_of_products: polynomial.products.clone(),
-            flattened_ml_extensions,
+            flattened_ml_extensions: polynomial.flattened_ml_extensions.clone(),
+            list_of_sparse_products: polynomial.sparse_products.clone(),
+            flattened_sparse_ml_extensions: polynomial.flattened_sparse_ml_extensions.clone(),
             num_vars: polynomial.num_variables,
             max_multiplicands: polynomial.max_multiplicands,
             round: 0,
@@ -76,6 +160,16 @@ impl<F: Field> IPForMLSumcheck<F> {
     pub fn prove_round(
         prover_state: &mut ProverState<F>,
         v_msg: &Option<VerifierMsg<F>>,
+    ) -> ProverMsg<F> {
+        Self::prove_weighted_round(prover_state, v_msg, None)
+    }
+
+    /// Same as `prove_round`, but the terms of the sum at `b` are multiplied by `weights[b]`, where
+    /// `weights` has one entry per point of the remaining hypercube after the current variable.
+    pub(crate) fn prove_weighted_round(
+        prover_state: &mut ProverState<F>,
+        v_msg: &Option<VerifierMsg<F>>,
+        weights: Option<&[F]>,
     ) -> ProverMsg<F> {
         if let Some(msg) = v_msg {
             if prover_state.round == 0 {
@@ -87,7 +181,10 @@ impl<F: Field> IPForMLSumcheck<F> {
             let i = prover_state.round;
             let r = prover_state.randomness[i - 1];
             cfg_iter_mut!(prover_state.flattened_ml_extensions).for_each(|multiplicand| {
-                *multiplicand = multiplicand.fix_variables(&[r]);
+                *multiplicand = Arc::new(fix_first_variable(multiplicand, r));
+            });
+            cfg_iter_mut!(prover_state.flattened_sparse_ml_extensions).for_each(|multiplicand| {
+                *multiplicand = Arc::new(multiplicand.fix_variables(&[r]));
             });
         } else if prover_state.round > 0 {
             panic!("verifier message is empty");
@@ -102,43 +199,48 @@ impl<F: Field> IPForMLSumcheck<F> {
         let i = prover_state.round;
         let nv = prover_state.num_vars;
         let degree = prover_state.max_multiplicands; // the degree of univariate polynomial sent by prover at this round
+        if let Some(weights) = weights {
+            assert_eq!(weights.len(), 1 << (nv - i), "wrong number of weights");
+        }
+        let scale = |coefficient: F, b: usize| weights.map_or(coefficient, |w| coefficient * w[b]);
 
         #[cfg(not(feature = "parallel"))]
         let zeros = (vec![F::zero(); degree + 1], vec![F::zero(); degree + 1]);
         #[cfg(feature = "parallel")]
         let zeros = || (vec![F::zero(); degree + 1], vec![F::zero(); degree + 1]);
 
-        // generate sum
+        // generate sum of the products of dense multiplicands
         let fold_result = ark_std::cfg_into_iter!(0..1 << (nv - i), 1 << 10).fold(
             zeros,
             |(mut products_sum, mut product), b| {
                 // In effect, this fold is essentially doing simply:
                 // for b in 0..1 << (nv - i) {
-                for (coefficient, products) in &prover_state.list_of_products {
-                    product.fill(*coefficient);
-                    for &jth_product in products {
-                        let table = &prover_state.flattened_ml_extensions[jth_product];
-                        let mut start = table[b << 1];
-                        let step = table[(b << 1) + 1] - start;
-                        for p in product.iter_mut() {
-                            *p *= start;
-                            start += step;
-                        }
-                    }
-                    for t in 0..degree + 1 {
-                        products_sum[t] += product[t];
-                    }
+                for ((coefficient, products), sparse) in prover_state
+                    .list_of_products
+                    .iter()
+                    .zip(&prover_state.list_of_sparse_products)
+                    .filter(|(_, sparse)| sparse.is_empty())
+                {
+                    add_product_at(
+                        prover_state,
+                        scale(*coefficient, b),
+                        products,
+                        sparse,
+                        b,
+                        &mut product,
+                        &mut products_sum,
+                    );
                 }
                 (products_sum, product)
             },
         );
 
         #[cfg(not(feature = "parallel"))]
-        let products_sum = fold_result.0;
+        let mut products_sum = fold_result.0;
 
         // When rayon is used, the `fold` operation results in a iterator of `Vec<F>` rather than a single `Vec<F>`. In this case, we simply need to sum them.
         #[cfg(feature = "parallel")]
-        let products_sum = fold_result.map(|scratch| scratch.0).reduce(
+        let mut products_sum = fold_result.map(|scratch| scratch.0).reduce(
             || vec![F::zero(); degree + 1],
             |mut overall_products_sum, sublist_sum| {
                 overall_products_sum
@@ -149,6 +251,35 @@ impl<F: Field> IPForMLSumcheck<F> {
             },
         );
 
+        // a product with sparse multiplicands vanishes at `b` unless its sparse multiplicand with
+        // the fewest nonzero entries is nonzero at `2b` or `2b + 1`, so only those `b` are visited
+        let mut product = vec![F::zero(); degree + 1];
+        for ((coefficient, products), sparse) in prover_state
+            .list_of_products
+            .iter()
+            .zip(&prover_state.list_of_sparse_products)
+            .filter(|(_, sparse)| !sparse.is_empty())
+        {
+            let sparsest = sparse
+                .iter()
+                .map(|&j| &prover_state.flattened_sparse_ml_extensions[j])
+                .min_by_key(|m| m.evaluations.len())
+                .unwrap();
+            let mut points: Vec<_> = sparsest.evaluations.keys().map(|x| x >> 1).collect();
+            points.dedup();
+            for b in points {
+                add_product_at(
+                    prover_state,
+                    scale(*coefficient, b),
+                    products,
+                    sparse,
+                    b,
+                    &mut product,
+                    &mut products_sum,
+                );
+            }
+        }
+
         ProverMsg {
             evaluations: products_sum,
         }
@@ -1,6 +1,6 @@
 //! Verifier
 use crate::ml_sumcheck::data_structures::PolynomialInfo;
-use crate::ml_sumcheck::protocol::prover::ProverMsg;
+use crate::ml_sumcheck::protocol::prover::{CompressedProverMsg, ProverMsg};
 use crate::ml_sumcheck::protocol::IPForMLSumcheck;
 use ark_ff::Field;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
@@ -24,6 +24,9 @@ pub struct VerifierState<F: Field> {
     polynomials_received: Vec<Vec<F>>,
     /// a list storing the randomness sampled by the verifier at each round
     randomness: Vec<F>,
+    /// `P(r)` of the last round, or the claimed sum before the first round, when the claimed sum
+    /// is known from the start and rounds are checked on receipt
+    expected: Option<F>,
 }
 /// Subclaim when verifier is convinced
 pub struct SubClaim<F: Field> {
@@ -33,6 +36,16 @@ pub struct SubClaim<F: Field> {
     pub expected_evaluation: F,
 }
 
+/// Subclaim of the zero-knowledge sumcheck when verifier is convinced
+pub struct ZKSubClaim<F: Field> {
+    /// the multi-dimensional point that this multilinear extension is evaluated to
+    pub point: Vec<F>,
+    /// the expected evaluation of the polynomial
+    pub expected_evaluation: F,
+    /// the evaluation of the masking polynomial, checked against its commitment
+    pub mask_evaluation: F,
+}
+
 impl<F: Field> IPForMLSumcheck<F> {
     /// initialize the verifier
     pub fn verifier_init(index_info: &PolynomialInfo) -> VerifierState<F> {
@@ -40,9 +53,22 @@ impl<F: Field> IPForMLSumcheck<F> {
             round: 1,
             nv: index_info.num_variables,
             max_multiplicands: index_info.max_multiplicands,
-            finished: false,
+            finished: index_info.num_variables == 0,
             polynomials_received: Vec::with_capacity(index_info.num_variables),
             randomness: Vec::with_capacity(index_info.num_variables),
+            expected: None,
+        }
+    }
+
+    /// initialize the verifier with the claimed sum, so that each round can be checked with
+    /// `verify_round_with_claim` as soon as the prover message is received
+    pub fn verifier_init_with_claim(
+        index_info: &PolynomialInfo,
+        claimed_sum: F,
+    ) -> VerifierState<F> {
+        VerifierState {
+            expected: Some(claimed_sum),
+            ..Self::verifier_init(index_info)
         }
     }
 
@@ -51,13 +77,19 @@ impl<F: Field> IPForMLSumcheck<F> {
     /// Normally, this function should perform actual verification. Instead, `verify_round` only samples
     /// and stores randomness and perform verifications altogether in `check_and_generate_subclaim` at
     /// the last step.
+    ///
+    /// Returns `None` if the verifier is already finished. The extra message is kept, so that
+    /// `check_and_generate_subclaim` rejects the proof.
     pub fn verify_round<R: RngCore>(
         prover_msg: ProverMsg<F>,
         verifier_state: &mut VerifierState<F>,
         rng: &mut R,
     ) -> Option<VerifierMsg<F>> {
         if verifier_state.finished {
-            panic!("Incorrect verifier state: Verifier is already finished.");
+            verifier_state
+                .polynomials_received
+                .push(prover_msg.evaluations);
+            return None;
         }
 
         // Now, verifier should check if the received P(0) + P(1) = expected. The check is moved to
@@ -82,6 +114,98 @@ impl<F: Field> IPForMLSumcheck<F> {
         Some(msg)
     }
 
+    /// Run verifier at current round, checking the prover message before sampling randomness.
+    ///
+    /// The state must come from `verifier_init_with_claim`. Unlike `verify_round`, the check
+    /// `P(0) + P(1) = expected` is done on receipt, so that an interactive verifier can abort at
+    /// the first bad round. The error names the index of that round, starting from 0.
+    pub fn verify_round_with_claim<R: RngCore>(
+        prover_msg: ProverMsg<F>,
+        verifier_state: &mut VerifierState<F>,
+        rng: &mut R,
+    ) -> Result<VerifierMsg<F>, crate::Error> {
+        let expected = verifier_state
+            .expected
+            .ok_or_else(|| crate::Error::OtherError("Verifier state has no claimed sum.".into()))?;
+        if verifier_state.finished {
+            return Err(crate::Error::Reject(Some(
+                "Proof has more messages than rounds.".into(),
+            )));
+        }
+        let round = verifier_state.round - 1;
+        let evaluations = prover_msg.evaluations;
+        if evaluations.len() != verifier_state.max_multiplicands + 1 || evaluations.len() < 2 {
+            return Err(crate::Error::Reject(Some(format!(
+                "Round {}: incorrect number of evaluations.",
+                round
+            ))));
+        }
+        if evaluations[0] + evaluations[1] != expected {
+            return Err(crate::Error::Reject(Some(format!(
+                "Round {}: prover message is not consistent with the claim.",
+                round
+            ))));
+        }
+
+        let msg = Self::sample_round(rng);
+        verifier_state.expected = Some(interpolate_uni_poly(&evaluations, msg.randomness));
+        verifier_state.randomness.push(msg.randomness);
+        verifier_state.polynomials_received.push(evaluations);
+        if verifier_state.round == verifier_state.nv {
+            verifier_state.finished = true;
+        } else {
+            verifier_state.round += 1;
+        }
+        Ok(msg)
+    }
+
+    /// Recovers the prover message of the current round from its compressed form, using the claim
+    /// of the round as `P(1) = claim - P(0)`.
+    ///
+    /// The state must come from `verifier_init_with_claim`. The recovered message passes the check
+    /// of `verify_round_with_claim` by construction, so a wrong message is only detected by the
+    /// final subclaim.
+    pub fn decompress_round(
+        prover_msg: &CompressedProverMsg<F>,
+        verifier_state: &VerifierState<F>,
+    ) -> Result<ProverMsg<F>, crate::Error> {
+        let claim = verifier_state
+            .expected
+            .ok_or_else(|| crate::Error::OtherError("Verifier state has no claimed sum.".into()))?;
+        let evaluations = &prover_msg.evaluations;
+        if evaluations.len() != verifier_state.max_multiplicands || evaluations.is_empty() {
+            return Err(crate::Error::Reject(Some(format!(
+                "Round {}: incorrect number of evaluations.",
+                verifier_state.round - 1
+            ))));
+        }
+        let mut decompressed = Vec::with_capacity(evaluations.len() + 1);
+        decompressed.push(evaluations[0]);
+        decompressed.push(claim - evaluations[0]);
+        decompressed.extend_from_slice(&evaluations[1..]);
+        Ok(ProverMsg {
+            evaluations: decompressed,
+        })
+    }
+
+    /// generate the subclaim after all rounds were checked with `verify_round_with_claim`
+    pub fn generate_subclaim(
+        verifier_state: VerifierState<F>,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        let expected = verifier_state
+            .expected
+            .ok_or_else(|| crate::Error::OtherError("Verifier state has no claimed sum.".into()))?;
+        if verifier_state.randomness.len() != verifier_state.nv {
+            return Err(crate::Error::Reject(Some(
+                "Verifier has not finished.".into(),
+            )));
+        }
+        Ok(SubClaim {
+            point: verifier_state.randomness,
+            expected_evaluation: expected,
+        })
+    }
+
     /// verify the sumcheck phase, and generate the subclaim
     ///
     /// If the asserted sum is correct, then the multilinear polynomial evaluated at `subclaim.point`
@@ -92,17 +216,24 @@ impl<F: Field> IPForMLSumcheck<F> {
         asserted_sum: F,
     ) -> Result<SubClaim<F>, crate::Error> {
         if !verifier_state.finished {
-            panic!("Verifier has not finished.");
+            return Err(crate::Error::Reject(Some(
+                "Verifier has not finished: insufficient rounds.".into(),
+            )));
         }
 
         let mut expected = asserted_sum;
         if verifier_state.polynomials_received.len() != verifier_state.nv {
-            panic!("insufficient rounds");
+            return Err(crate::Error::Reject(Some(
+                "Proof has more messages than rounds.".into(),
+            )));
         }
         for i in 0..verifier_state.nv {
             let evaluations = &verifier_state.polynomials_received[i];
-            if evaluations.len() != verifier_state.max_multiplicands + 1 {
-                panic!("incorrect number of evaluations");
+            if evaluations.len() != verifier_state.max_multiplicands + 1 || evaluations.len() < 2 {
+                return Err(crate::Error::Reject(Some(format!(
+                    "Round {}: incorrect number of evaluations.",
+                    i
+                ))));
             }
             let p0 = evaluations[0];
             let p1 = evaluations[1];
@@ -0,0 +1,148 @@
+//! Zerocheck
+//!
+//! Proves that `f(x) = 0` for all `x` in {0,1}^`num_vars` with the sumcheck of
+//! `sum_x eq(τ, x) f(x) = 0`, for a random `τ`. Following
+//! [Gru24](https://eprint.iacr.org/2024/108) (Section 3), `eq(τ, ·)` is kept out of the products:
+//! the round polynomial of round `i` factors as
+//!
+//! $$s_i(X) = eq(τ_{<i}, r_{<i}) \cdot eq(τ_i, X) \cdot t_i(X),$$
+//!
+//! where `t_i(X) = sum_b eq(τ_{>i}, b) f(r_{<i}, X, b)` has the degree of `f`. The prover only
+//! sends `t_i`, which has one evaluation fewer than `s_i`, and computes it by weighting the terms
+//! of `f` with the table of `eq(τ_{>i}, ·)` instead of multiplying by an extra multiplicand.
+use crate::ml_sumcheck::data_structures::{ListOfProductsOfPolynomials, PolynomialInfo};
+use crate::ml_sumcheck::protocol::prover::{ProverMsg, ProverState};
+use crate::ml_sumcheck::protocol::verifier::{interpolate_uni_poly, SubClaim, VerifierMsg};
+use crate::ml_sumcheck::protocol::IPForMLSumcheck;
+use crate::utils::eq_evaluations;
+use ark_ff::Field;
+use ark_std::rand::RngCore;
+use ark_std::vec::Vec;
+
+/// Prover State of the zerocheck
+#[derive(Clone)]
+pub struct ZerocheckProverState<F: Field> {
+    /// state of the sumcheck of `f`, without the factor `eq(τ, ·)`
+    pub sumcheck_state: ProverState<F>,
+    /// table of `eq(τ_{>i}, ·)` at round `i`
+    eq_table: Vec<F>,
+}
+
+/// Verifier State of the zerocheck
+pub struct ZerocheckVerifierState<F: Field> {
+    tau: Vec<F>,
+    max_multiplicands: usize,
+    /// a list storing the randomness sampled by the verifier at each round
+    randomness: Vec<F>,
+    /// `t_i(r_i)` of the last round, which is the claimed sum of `eq(τ_{>i}, ·) f(r_{≤i}, ·)`
+    expected: F,
+}
+
+impl<F: Field> IPForMLSumcheck<F> {
+    /// initialize the prover to argue that `polynomial` vanishes on {0,1}^`num_vars`, given the
+    /// random point `tau` of the verifier
+    pub fn zerocheck_prover_init(
+        polynomial: &ListOfProductsOfPolynomials<F>,
+        tau: &[F],
+    ) -> ZerocheckProverState<F> {
+        assert_eq!(
+            tau.len(),
+            polynomial.num_variables,
+            "tau has the wrong number of variables"
+        );
+        ZerocheckProverState {
+            sumcheck_state: Self::prover_init(polynomial),
+            eq_table: eq_evaluations(&tau[1..]),
+        }
+    }
+
+    /// receive message from verifier, and generate the evaluations of `t_i` at `0, ..., degree`
+    pub fn prove_zerocheck_round(
+        prover_state: &mut ZerocheckProverState<F>,
+        v_msg: &Option<VerifierMsg<F>>,
+    ) -> ProverMsg<F> {
+        if v_msg.is_some() && prover_state.sumcheck_state.round > 0 {
+            // eq(τ_{>i+1}, b) = eq(τ_{>i}, (0, b)) + eq(τ_{>i}, (1, b))
+            let table = &prover_state.eq_table;
+            prover_state.eq_table = table.chunks(2).map(|pair| pair[0] + pair[1]).collect();
+        }
+        Self::prove_weighted_round(
+            &mut prover_state.sumcheck_state,
+            v_msg,
+            Some(&prover_state.eq_table),
+        )
+    }
+
+    /// initialize the verifier of the zerocheck with its random point `tau`
+    pub fn zerocheck_verifier_init(
+        index_info: &PolynomialInfo,
+        tau: Vec<F>,
+    ) -> ZerocheckVerifierState<F> {
+        assert_eq!(
+            tau.len(),
+            index_info.num_variables,
+            "tau has the wrong number of variables"
+        );
+        ZerocheckVerifierState {
+            tau,
+            max_multiplicands: index_info.max_multiplicands,
+            randomness: Vec::with_capacity(index_info.num_variables),
+            expected: F::zero(),
+        }
+    }
+
+    /// Run the verifier of the zerocheck at current round, given the evaluations of `t_i`.
+    ///
+    /// `s_i(0) + s_i(1)` is the claim of the round up to the factor `eq(τ_{<i}, r_{<i})`, so the
+    /// check is `(1 - τ_i) t_i(0) + τ_i t_i(1) = t_{i-1}(r_{i-1})`, with `t_0(r_0) = 0`. The error
+    /// names the index of the first bad round, starting from 0.
+    pub fn verify_zerocheck_round<R: RngCore>(
+        prover_msg: ProverMsg<F>,
+        verifier_state: &mut ZerocheckVerifierState<F>,
+        rng: &mut R,
+    ) -> Result<VerifierMsg<F>, crate::Error> {
+        let round = verifier_state.randomness.len();
+        if round == verifier_state.tau.len() {
+            return Err(crate::Error::Reject(Some(
+                "Proof has more messages than rounds.".into(),
+            )));
+        }
+        let evaluations = prover_msg.evaluations;
+        if evaluations.len() != verifier_state.max_multiplicands + 1 || evaluations.len() < 2 {
+            return Err(crate::Error::Reject(Some(format!(
+                "Round {}: incorrect number of evaluations.",
+                round
+            ))));
+        }
+        let tau = verifier_state.tau[round];
+        if (F::one() - tau) * evaluations[0] + tau * evaluations[1] != verifier_state.expected {
+            return Err(crate::Error::Reject(Some(format!(
+                "Round {}: prover message is not consistent with the claim.",
+                round
+            ))));
+        }
+
+        let msg = Self::sample_round(rng);
+        verifier_state.expected = interpolate_uni_poly(&evaluations, msg.randomness);
+        verifier_state.randomness.push(msg.randomness);
+        Ok(msg)
+    }
+
+    /// generate the subclaim after all rounds were checked with `verify_zerocheck_round`
+    ///
+    /// The factor `eq(τ, r)` cancels out, so the subclaim is on `f` itself: if `f` vanishes on the
+    /// hypercube, then `f(subclaim.point) = subclaim.expected_evaluation`.
+    pub fn zerocheck_subclaim(
+        verifier_state: ZerocheckVerifierState<F>,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        if verifier_state.randomness.len() != verifier_state.tau.len() {
+            return Err(crate::Error::Reject(Some(
+                "Verifier has not finished.".into(),
+            )));
+        }
+        Ok(SubClaim {
+            point: verifier_state.randomness,
+            expected_evaluation: verifier_state.expected,
+        })
+    }
+}
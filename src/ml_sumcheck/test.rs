@@ -1,22 +1,28 @@
-use crate::ml_sumcheck::data_structures::ListOfProductsOfPolynomials;
+use crate::ml_sumcheck::data_structures::{ListOfProductsOfPolynomials, TransparentMaskCommitment};
+use crate::ml_sumcheck::protocol::verifier::{interpolate_uni_poly, VerifierMsg};
 use crate::ml_sumcheck::protocol::IPForMLSumcheck;
 use crate::ml_sumcheck::MLSumcheck;
 use crate::rng::Blake2b512Rng;
 use crate::rng::FeedableRNG;
-use ark_ff::Field;
-use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+use ark_ff::fields::{Fp64, MontBackend, MontConfig};
+use ark_ff::{Field, One, PrimeField, Zero};
+use ark_poly::{DenseMultilinearExtension, MultilinearExtension, SparseMultilinearExtension};
+use ark_serialize::CanonicalSerialize;
+use ark_std::convert::TryInto;
 use ark_std::rand::Rng;
 use ark_std::rand::RngCore;
-use ark_std::rc::Rc;
+use ark_std::sync::Arc;
 use ark_std::vec::Vec;
 use ark_std::{test_rng, UniformRand};
 use ark_test_curves::bls12_381::Fr;
 
+type Mask = TransparentMaskCommitment<Fr>;
+
 fn random_product<F: Field, R: RngCore>(
     nv: usize,
     num_multiplicands: usize,
     rng: &mut R,
-) -> (Vec<Rc<DenseMultilinearExtension<F>>>, F) {
+) -> (Vec<Arc<DenseMultilinearExtension<F>>>, F) {
     let mut multiplicands = Vec::with_capacity(num_multiplicands);
     for _ in 0..num_multiplicands {
         multiplicands.push(Vec::with_capacity(1 << nv))
@@ -36,7 +42,7 @@ fn random_product<F: Field, R: RngCore>(
     (
         multiplicands
             .into_iter()
-            .map(|x| Rc::new(DenseMultilinearExtension::from_evaluations_vec(nv, x)))
+            .map(|x| Arc::new(DenseMultilinearExtension::from_evaluations_vec(nv, x)))
             .collect(),
         sum,
     )
@@ -96,6 +102,32 @@ fn test_protocol(nv: usize, num_multiplicands_range: (usize, usize), num_product
     );
 }
 
+fn test_protocol_with_claim(
+    nv: usize,
+    num_multiplicands_range: (usize, usize),
+    num_products: usize,
+) {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) =
+        random_list_of_products::<Fr, _>(nv, num_multiplicands_range, num_products, &mut rng);
+    let mut prover_state = IPForMLSumcheck::prover_init(&poly);
+    let mut verifier_state = IPForMLSumcheck::verifier_init_with_claim(&poly.info(), asserted_sum);
+    let mut verifier_msg = None;
+    for _ in 0..poly.num_variables {
+        let prover_message = IPForMLSumcheck::prove_round(&mut prover_state, &verifier_msg);
+        verifier_msg = Some(
+            IPForMLSumcheck::verify_round_with_claim(prover_message, &mut verifier_state, &mut rng)
+                .expect("round rejected"),
+        );
+    }
+    let subclaim =
+        IPForMLSumcheck::generate_subclaim(verifier_state).expect("fail to generate subclaim");
+    assert!(
+        poly.evaluate(&subclaim.point) == subclaim.expected_evaluation,
+        "wrong subclaim"
+    );
+}
+
 fn test_polynomial_as_subprotocol(
     nv: usize,
     num_multiplicands_range: (usize, usize),
@@ -218,7 +250,7 @@ fn test_extract_sum() {
 fn test_shared_reference() {
     let mut rng = test_rng();
     let ml_extensions: Vec<_> = (0..5)
-        .map(|_| Rc::new(DenseMultilinearExtension::<Fr>::rand(8, &mut rng)))
+        .map(|_| Arc::new(DenseMultilinearExtension::<Fr>::rand(8, &mut rng)))
         .collect();
     let mut poly = ListOfProductsOfPolynomials::new(8);
     poly.add_product(
@@ -267,3 +299,728 @@ fn test_shared_reference() {
         "wrong subclaim"
     );
 }
+
+#[test]
+fn test_zk_protocol() {
+    let mut rng = test_rng();
+    for &(nv, num_multiplicands_range, num_products) in &[(1, (1, 4), 3), (8, (3, 6), 4)] {
+        let (poly, asserted_sum) =
+            random_list_of_products::<Fr, _>(nv, num_multiplicands_range, num_products, &mut rng);
+        let poly_info = poly.info();
+        let (proof, mask, opening) =
+            MLSumcheck::prove_zk::<Mask, _>(&(), &poly, &mut rng).expect("fail to prove");
+        let verify = |sum, proof| {
+            MLSumcheck::verify_zk::<Mask>(&(), &poly_info, sum, &mask, proof, &opening)
+        };
+        let subclaim = verify(asserted_sum, &proof).expect("fail to verify");
+        assert_eq!(poly.evaluate(&subclaim.point), subclaim.expected_evaluation);
+        assert_eq!(mask.evaluate(&subclaim.point), subclaim.mask_evaluation);
+
+        // a wrong sum is rejected
+        let result = verify(asserted_sum + Fr::from(1u64), &proof);
+        assert!(result.map_or(true, |s| poly.evaluate(&s.point) != s.expected_evaluation));
+
+        // malformed proofs are rejected
+        let mut truncated = proof.clone();
+        truncated.msgs.pop();
+        assert!(verify(asserted_sum, &truncated).is_err());
+        let mut short_message = proof.clone();
+        short_message.msgs[0].evaluations.pop();
+        assert!(verify(asserted_sum, &short_message).is_err());
+    }
+}
+
+#[test]
+/// Test that the evaluation of the masking polynomial is bound by its commitment, so that it can
+/// not be chosen after `rho` and the point are known.
+fn test_zk_mask_bound_by_commitment() {
+    let mut rng = test_rng();
+    let nv = 4;
+    let (poly, asserted_sum) = random_list_of_products::<Fr, _>(nv, (2, 4), 3, &mut rng);
+    let info = poly.info();
+    let (mut proof, mask, opening) = MLSumcheck::prove_zk::<Mask, _>(&(), &poly, &mut rng).unwrap();
+
+    // a mask evaluation that makes the subclaim true for a wrong sum is rejected
+    let wrong_sum = asserted_sum + Fr::from(1u64);
+    let mut fs_rng = Blake2b512Rng::setup();
+    fs_rng.feed(&info).unwrap();
+    fs_rng.feed(&mask).unwrap();
+    fs_rng.feed(&proof.mask_sum).unwrap();
+    let rho = Fr::rand(&mut fs_rng);
+    proof.mask_evaluation += rho.inverse().unwrap();
+    let result = MLSumcheck::verify_zk::<Mask>(&(), &info, wrong_sum, &mask, &proof, &opening);
+    assert!(matches!(result, Err(crate::Error::Reject(_))));
+
+    // a commitment to another mask is rejected
+    let (proof, _, opening) = MLSumcheck::prove_zk::<Mask, _>(&(), &poly, &mut rng).unwrap();
+    let result = MLSumcheck::verify_zk::<Mask>(&(), &info, asserted_sum, &mask, &proof, &opening);
+    assert!(result.map_or(true, |s| poly.evaluate(&s.point) != s.expected_evaluation));
+
+    // constant polynomials are errors instead of panics
+    let constant = ListOfProductsOfPolynomials::<Fr>::new(0);
+    assert!(MLSumcheck::prove_zk::<Mask, _>(&(), &constant, &mut rng).is_err());
+}
+
+#[derive(MontConfig)]
+#[modulus = "17"]
+#[generator = "3"]
+struct F17Config;
+type F17 = Fp64<MontBackend<F17Config, 1>>;
+
+#[test]
+/// Test that, over a small field, each masked message is uniformly distributed for two
+/// polynomials with the same sum, while the unmasked messages differ.
+fn test_zk_hiding() {
+    let mut rng = test_rng();
+    let nv = 3;
+    let a: Vec<_> = (0..1 << nv).map(|_| F17::rand(&mut rng)).collect();
+    let b: Vec<_> = (0..1 << nv).map(|_| F17::rand(&mut rng)).collect();
+    let poly_from = |a: &[F17], b: &[F17]| {
+        let mut poly = ListOfProductsOfPolynomials::new(nv);
+        poly.add_product(
+            vec![
+                Arc::new(DenseMultilinearExtension::from_evaluations_slice(nv, a)),
+                Arc::new(DenseMultilinearExtension::from_evaluations_slice(nv, b)),
+            ],
+            F17::one(),
+        );
+        poly
+    };
+    let p = poly_from(&a, &b);
+    // swapping two points of the hypercube keeps the sum
+    let (mut a_swapped, mut b_swapped) = (a.clone(), b.clone());
+    a_swapped.swap(0, 7);
+    b_swapped.swap(0, 7);
+    let q = poly_from(&a_swapped, &b_swapped);
+    assert_ne!(
+        MLSumcheck::prove(&p).unwrap()[0].evaluations,
+        MLSumcheck::prove(&q).unwrap()[0].evaluations
+    );
+
+    let num_runs = 17 * 100;
+    for poly in [p, q] {
+        let mut histograms = vec![vec![0usize; 17]; nv];
+        let mut num_samples = 0;
+        for _ in 0..num_runs {
+            let (proof, commitment, _) =
+                MLSumcheck::prove_zk::<TransparentMaskCommitment<F17>, _>(&(), &poly, &mut rng)
+                    .unwrap();
+            // messages are not masked when rho is zero, which happens with probability 1/17
+            let mut fs_rng = Blake2b512Rng::setup();
+            fs_rng.feed(&poly.info()).unwrap();
+            fs_rng.feed(&commitment).unwrap();
+            fs_rng.feed(&proof.mask_sum).unwrap();
+            if F17::rand(&mut fs_rng).is_zero() {
+                continue;
+            }
+            num_samples += 1;
+            for (histogram, msg) in histograms.iter_mut().zip(&proof.msgs) {
+                histogram[msg.evaluations[0].into_bigint().0[0] as usize] += 1;
+            }
+        }
+        let expected = num_samples / 17;
+        for histogram in histograms {
+            for count in histogram {
+                assert!(count > expected / 2 && count < expected * 3 / 2);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_normal_polynomial_with_claim() {
+    test_protocol_with_claim(12, (4, 9), 5);
+    test_protocol_with_claim(1, (1, 3), 2);
+}
+
+#[test]
+fn test_early_rejection() {
+    let nv = 8;
+    let bad_round = 3;
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_list_of_products::<Fr, _>(nv, (2, 4), 3, &mut rng);
+    let mut prover_state = IPForMLSumcheck::prover_init(&poly);
+    let mut verifier_state = IPForMLSumcheck::verifier_init_with_claim(&poly.info(), asserted_sum);
+    let mut verifier_msg = None;
+    for round in 0..nv {
+        let mut prover_message = IPForMLSumcheck::prove_round(&mut prover_state, &verifier_msg);
+        if round == bad_round {
+            prover_message.evaluations[0] += Fr::one();
+        }
+        let result =
+            IPForMLSumcheck::verify_round_with_claim(prover_message, &mut verifier_state, &mut rng);
+        if round < bad_round {
+            verifier_msg = Some(result.expect("honest round rejected"));
+        } else {
+            match result {
+                Err(crate::Error::Reject(Some(msg))) => assert!(msg.starts_with("Round 3:")),
+                _ => panic!("bad round accepted"),
+            }
+            break;
+        }
+    }
+
+    // the non-interactive verifier reports the same round
+    let mut proof = MLSumcheck::prove(&poly).unwrap();
+    proof[bad_round].evaluations[1] -= Fr::one();
+    match MLSumcheck::verify(&poly.info(), asserted_sum, &proof) {
+        Err(crate::Error::Reject(Some(msg))) => assert!(msg.starts_with("Round 3:")),
+        _ => panic!("bad proof accepted"),
+    }
+}
+
+/// Asserts that a malformed proof is rejected with an error.
+fn assert_rejected<T>(result: Result<T, crate::Error>, case: &str) {
+    match result {
+        Err(crate::Error::Reject(_)) => {}
+        Err(e) => panic!("{}: unexpected error {:?}", case, e),
+        Ok(_) => panic!("{}: malformed proof accepted", case),
+    }
+}
+
+#[test]
+fn test_malformed_proofs() {
+    let nv = 6;
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_list_of_products::<Fr, _>(nv, (2, 4), 3, &mut rng);
+    let info = poly.info();
+    let proof = MLSumcheck::prove(&poly).unwrap();
+    assert!(MLSumcheck::verify(&info, asserted_sum, &proof).is_ok());
+
+    let mut truncated = proof.clone();
+    truncated.pop();
+    assert_rejected(
+        MLSumcheck::verify(&info, asserted_sum, &truncated),
+        "truncated",
+    );
+    assert_rejected(
+        MLSumcheck::verify(&info, asserted_sum, &Vec::new()),
+        "empty",
+    );
+
+    let mut extra = proof.clone();
+    extra.push(proof[0].clone());
+    assert_rejected(
+        MLSumcheck::verify(&info, asserted_sum, &extra),
+        "extra message",
+    );
+
+    let mut high_degree = proof.clone();
+    high_degree[2].evaluations.push(Fr::one());
+    assert_rejected(
+        MLSumcheck::verify(&info, asserted_sum, &high_degree),
+        "high degree",
+    );
+
+    let mut low_degree = proof.clone();
+    low_degree[nv - 1].evaluations.pop();
+    assert_rejected(
+        MLSumcheck::verify(&info, asserted_sum, &low_degree),
+        "low degree",
+    );
+
+    let mut no_evaluations = proof.clone();
+    no_evaluations[0].evaluations.clear();
+    assert_rejected(
+        MLSumcheck::verify(&info, asserted_sum, &no_evaluations),
+        "no evaluations",
+    );
+
+    // malformed proofs for a polynomial whose products have a single multiplicand, so that one
+    // evaluation per round is not enough to check `P(0) + P(1)`
+    let mut linear_info = info.clone();
+    linear_info.max_multiplicands = 0;
+    let single: Vec<_> = proof
+        .iter()
+        .map(|msg| {
+            let mut msg = msg.clone();
+            msg.evaluations.truncate(1);
+            msg
+        })
+        .collect();
+    assert_rejected(
+        MLSumcheck::verify(&linear_info, asserted_sum, &single),
+        "single evaluation",
+    );
+
+    let (mut malformed_zk, mask, opening) =
+        MLSumcheck::prove_zk::<Mask, _>(&(), &poly, &mut rng).unwrap();
+    malformed_zk.msgs.pop();
+    assert_rejected(
+        MLSumcheck::verify_zk::<Mask>(&(), &info, asserted_sum, &mask, &malformed_zk, &opening),
+        "truncated zk",
+    );
+}
+
+#[test]
+fn test_malformed_proofs_deferred() {
+    let nv = 4;
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_list_of_products::<Fr, _>(nv, (2, 4), 3, &mut rng);
+    let info = poly.info();
+    let proof = MLSumcheck::prove(&poly).unwrap();
+
+    // run the deferred verifier on `msgs`, feeding every message even after the last round
+    let verify_deferred = |msgs: &[crate::ml_sumcheck::protocol::prover::ProverMsg<Fr>]| {
+        let mut rng = test_rng();
+        let mut verifier_state = IPForMLSumcheck::verifier_init(&info);
+        for msg in msgs {
+            IPForMLSumcheck::verify_round(msg.clone(), &mut verifier_state, &mut rng);
+        }
+        IPForMLSumcheck::check_and_generate_subclaim(verifier_state, asserted_sum)
+    };
+
+    assert_rejected(verify_deferred(&proof[..nv - 1]), "insufficient rounds");
+    let mut extra = proof.clone();
+    extra.push(proof[1].clone());
+    assert_rejected(verify_deferred(&extra), "extra message");
+    let mut high_degree = proof.clone();
+    high_degree[1].evaluations.push(Fr::zero());
+    assert_rejected(verify_deferred(&high_degree), "high degree");
+
+    // the incremental verifier rejects an extra message instead of panicking
+    let mut verifier_state = IPForMLSumcheck::verifier_init_with_claim(&info, asserted_sum);
+    let mut prover_state = IPForMLSumcheck::prover_init(&poly);
+    let mut verifier_msg = None;
+    for _ in 0..nv {
+        let msg = IPForMLSumcheck::prove_round(&mut prover_state, &verifier_msg);
+        verifier_msg = Some(
+            IPForMLSumcheck::verify_round_with_claim(msg, &mut verifier_state, &mut rng).unwrap(),
+        );
+    }
+    assert_rejected(
+        IPForMLSumcheck::verify_round_with_claim(proof[0].clone(), &mut verifier_state, &mut rng),
+        "extra message with claim",
+    );
+}
+
+#[test]
+fn test_compressed_proof() {
+    let mut rng = test_rng();
+    for (nv, range) in [(1, (1, 2)), (6, (2, 4)), (8, (3, 6))] {
+        let (poly, asserted_sum) = random_list_of_products::<Fr, _>(nv, range, 4, &mut rng);
+        let info = poly.info();
+        let proof = MLSumcheck::prove(&poly).unwrap();
+        let compressed = MLSumcheck::compress_proof(&proof);
+
+        let subclaim = MLSumcheck::verify(&info, asserted_sum, &proof).unwrap();
+        let compressed_subclaim =
+            MLSumcheck::verify_compressed(&info, asserted_sum, &compressed).unwrap();
+        assert_eq!(subclaim.point, compressed_subclaim.point);
+        assert_eq!(
+            subclaim.expected_evaluation,
+            compressed_subclaim.expected_evaluation
+        );
+        assert_eq!(
+            poly.evaluate(&compressed_subclaim.point),
+            compressed_subclaim.expected_evaluation
+        );
+
+        // one field element fewer per round
+        assert_eq!(
+            proof.compressed_size() - compressed.compressed_size(),
+            nv * Fr::one().compressed_size()
+        );
+    }
+}
+
+#[test]
+fn test_malformed_compressed_proofs() {
+    let nv = 6;
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_list_of_products::<Fr, _>(nv, (2, 4), 3, &mut rng);
+    let info = poly.info();
+    let compressed = MLSumcheck::compress_proof(&MLSumcheck::prove(&poly).unwrap());
+
+    // a wrong P(0) changes the derived P(1), so it is only caught by the final subclaim
+    let mut tampered = compressed.clone();
+    tampered[2].evaluations[0] += Fr::one();
+    let subclaim = MLSumcheck::verify_compressed(&info, asserted_sum, &tampered).unwrap();
+    assert_ne!(poly.evaluate(&subclaim.point), subclaim.expected_evaluation);
+
+    let subclaim =
+        MLSumcheck::verify_compressed(&info, asserted_sum + Fr::one(), &compressed).unwrap();
+    assert_ne!(poly.evaluate(&subclaim.point), subclaim.expected_evaluation);
+
+    let mut truncated = compressed.clone();
+    truncated.pop();
+    assert_rejected(
+        MLSumcheck::verify_compressed(&info, asserted_sum, &truncated),
+        "truncated",
+    );
+
+    let mut high_degree = compressed.clone();
+    high_degree[1].evaluations.push(Fr::one());
+    assert_rejected(
+        MLSumcheck::verify_compressed(&info, asserted_sum, &high_degree),
+        "high degree",
+    );
+
+    let mut no_evaluations = compressed;
+    no_evaluations[0].evaluations.clear();
+    assert_rejected(
+        MLSumcheck::verify_compressed(&info, asserted_sum, &no_evaluations),
+        "no evaluations",
+    );
+}
+
+#[test]
+fn test_prove_on_spawned_thread() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<ListOfProductsOfPolynomials<Fr>>();
+
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_list_of_products::<Fr, _>(8, (2, 4), 3, &mut rng);
+    let info = poly.info();
+    let proof = std::thread::spawn(move || MLSumcheck::prove(&poly).map(|proof| (poly, proof)));
+    let (poly, proof) = proof.join().unwrap().unwrap();
+    let subclaim = MLSumcheck::verify(&info, asserted_sum, &proof).unwrap();
+    assert_eq!(poly.evaluate(&subclaim.point), subclaim.expected_evaluation);
+}
+
+#[test]
+fn test_shared_multiplicands_across_threads() {
+    let mut rng = test_rng();
+    let nv = 6;
+    let shared = Arc::new(DenseMultilinearExtension::<Fr>::rand(nv, &mut rng));
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let other = Arc::new(DenseMultilinearExtension::<Fr>::rand(nv, &mut rng));
+            let shared = shared.clone();
+            std::thread::spawn(move || {
+                let mut poly = ListOfProductsOfPolynomials::new(nv);
+                poly.add_product(vec![shared.clone(), other.clone()], Fr::one());
+                poly.add_product(vec![shared.clone(), shared], Fr::from(2u64));
+                poly.add_product(vec![other], Fr::from(3u64));
+                // the shared multiplicand is stored once
+                assert_eq!(poly.flattened_ml_extensions.len(), 2);
+                let proof = MLSumcheck::prove(&poly).unwrap();
+                (poly, proof)
+            })
+        })
+        .collect();
+    for handle in handles {
+        let (poly, proof) = handle.join().unwrap();
+        let asserted_sum = MLSumcheck::extract_sum(&proof);
+        let subclaim = MLSumcheck::verify(&poly.info(), asserted_sum, &proof).unwrap();
+        assert_eq!(poly.evaluate(&subclaim.point), subclaim.expected_evaluation);
+    }
+}
+
+#[test]
+fn test_prover_shares_tables() {
+    let mut rng = test_rng();
+    let (poly, _) = random_list_of_products::<Fr, _>(6, (2, 4), 3, &mut rng);
+    let original: Vec<_> = poly
+        .flattened_ml_extensions
+        .iter()
+        .map(|m| m.as_ref().clone())
+        .collect();
+
+    let mut prover_state = IPForMLSumcheck::prover_init(&poly);
+    let prover_msg = IPForMLSumcheck::prove_round(&mut prover_state, &None);
+    for (shared, table) in prover_state
+        .flattened_ml_extensions
+        .iter()
+        .zip(&poly.flattened_ml_extensions)
+    {
+        assert!(Arc::ptr_eq(shared, table));
+    }
+
+    let r = Fr::rand(&mut rng);
+    let verifier_msg = Some(VerifierMsg { randomness: r });
+    IPForMLSumcheck::prove_round(&mut prover_state, &verifier_msg);
+    for ((fixed, table), original) in prover_state
+        .flattened_ml_extensions
+        .iter()
+        .zip(&poly.flattened_ml_extensions)
+        .zip(&original)
+    {
+        assert_eq!(table.as_ref(), original);
+        assert_eq!(fixed.as_ref(), &original.fix_variables(&[r]));
+    }
+    assert_eq!(prover_msg.evaluations.len(), poly.max_multiplicands + 1);
+}
+
+#[test]
+fn test_sparse_multiplicands() {
+    let nv = 10;
+    let mut rng = test_rng();
+    let sparse: Vec<_> = [1 << 4, 3, 1]
+        .iter()
+        .map(|&num_nonzero| {
+            Arc::new(SparseMultilinearExtension::<Fr>::rand_with_config(
+                nv,
+                num_nonzero,
+                &mut rng,
+            ))
+        })
+        .collect();
+    let dense: Vec<_> = (0..3)
+        .map(|_| Arc::new(DenseMultilinearExtension::<Fr>::rand(nv, &mut rng)))
+        .collect();
+    let coefficients: Vec<_> = (0..4).map(|_| Fr::rand(&mut rng)).collect();
+    let densify =
+        |m: &Arc<SparseMultilinearExtension<Fr>>| Arc::new(m.to_dense_multilinear_extension());
+    let densified: Vec<_> = sparse.iter().map(densify).collect();
+
+    let mut poly = ListOfProductsOfPolynomials::new(nv);
+    let mut dense_poly = ListOfProductsOfPolynomials::new(nv);
+    poly.add_product_with_sparse(
+        vec![sparse[0].clone()],
+        vec![dense[0].clone(), dense[1].clone()],
+        coefficients[0],
+    );
+    dense_poly.add_product(
+        vec![densified[0].clone(), dense[0].clone(), dense[1].clone()],
+        coefficients[0],
+    );
+    poly.add_product(vec![dense[1].clone(), dense[2].clone()], coefficients[1]);
+    dense_poly.add_product(vec![dense[1].clone(), dense[2].clone()], coefficients[1]);
+    poly.add_product_with_sparse(
+        vec![sparse[1].clone(), sparse[0].clone()],
+        vec![dense[2].clone()],
+        coefficients[2],
+    );
+    dense_poly.add_product(
+        vec![densified[1].clone(), densified[0].clone(), dense[2].clone()],
+        coefficients[2],
+    );
+    poly.add_product_with_sparse(vec![sparse[2].clone()], Vec::new(), coefficients[3]);
+    dense_poly.add_product(vec![densified[2].clone()], coefficients[3]);
+
+    // the shared sparse multiplicand is stored once
+    assert_eq!(poly.flattened_sparse_ml_extensions.len(), 3);
+    assert_eq!(poly.flattened_ml_extensions.len(), 3);
+    assert_eq!(poly.max_multiplicands, 3);
+
+    // the prover messages are the same as for the densified polynomial
+    let proof = MLSumcheck::prove(&poly).unwrap();
+    let dense_proof = MLSumcheck::prove(&dense_poly).unwrap();
+    for (msg, dense_msg) in proof.iter().zip(&dense_proof) {
+        assert_eq!(msg.evaluations(), dense_msg.evaluations());
+    }
+    let point: Vec<_> = (0..nv).map(|_| Fr::rand(&mut rng)).collect();
+    assert_eq!(poly.evaluate(&point), dense_poly.evaluate(&point));
+
+    let asserted_sum = MLSumcheck::extract_sum(&proof);
+    let subclaim = MLSumcheck::verify(&poly.info(), asserted_sum, &proof).unwrap();
+    assert_eq!(poly.evaluate(&subclaim.point), subclaim.expected_evaluation);
+}
+
+/// Returns `A B D - E`, where `E` is the entrywise product of `A`, `B` and `D`, so that the
+/// polynomial vanishes on the hypercube.
+fn vanishing_polynomial<R: RngCore>(nv: usize, rng: &mut R) -> ListOfProductsOfPolynomials<Fr> {
+    let (a, b, d) = (
+        DenseMultilinearExtension::<Fr>::rand(nv, rng),
+        DenseMultilinearExtension::<Fr>::rand(nv, rng),
+        DenseMultilinearExtension::<Fr>::rand(nv, rng),
+    );
+    let e: Vec<_> = (0..1 << nv).map(|x| a[x] * b[x] * d[x]).collect();
+    let e = DenseMultilinearExtension::from_evaluations_vec(nv, e);
+    let mut poly = ListOfProductsOfPolynomials::new(nv);
+    poly.add_product(vec![Arc::new(a), Arc::new(b), Arc::new(d)], Fr::one());
+    poly.add_product(vec![Arc::new(e)], -Fr::one());
+    poly
+}
+
+#[test]
+fn test_zerocheck() {
+    let mut rng = test_rng();
+    for nv in [1, 5, 9] {
+        let poly = vanishing_polynomial(nv, &mut rng);
+        let proof = MLSumcheck::prove_zerocheck(&poly).unwrap();
+        assert_eq!(proof.len(), nv);
+        assert!(proof
+            .iter()
+            .all(|msg| msg.evaluations().len() == poly.max_multiplicands + 1));
+        let subclaim = MLSumcheck::verify_zerocheck(&poly.info(), &proof).unwrap();
+        assert_eq!(poly.evaluate(&subclaim.point), subclaim.expected_evaluation);
+    }
+}
+
+#[test]
+fn test_zerocheck_round_polynomials() {
+    // the round polynomials of the sumcheck of eq(τ, ·) f are eq(τ_{<i}, r_{<i}) eq(τ_i, X) t_i(X)
+    let nv = 6;
+    let mut rng = test_rng();
+    let (poly, _) = random_list_of_products::<Fr, _>(nv, (2, 4), 3, &mut rng);
+    let degree = poly.max_multiplicands;
+    let tau: Vec<_> = (0..nv).map(|_| Fr::rand(&mut rng)).collect();
+    let eq = Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+        nv,
+        crate::utils::eq_evaluations(&tau),
+    ));
+    let mut eq_poly = ListOfProductsOfPolynomials::new(nv);
+    for (coefficient, product) in &poly.products {
+        let mut multiplicands: Vec<_> = product
+            .iter()
+            .map(|&i| poly.flattened_ml_extensions[i].clone())
+            .collect();
+        multiplicands.push(eq.clone());
+        eq_poly.add_product(multiplicands, *coefficient);
+    }
+
+    let mut zerocheck_state = IPForMLSumcheck::zerocheck_prover_init(&poly, &tau);
+    let mut sumcheck_state = IPForMLSumcheck::prover_init(&eq_poly);
+    let mut verifier_msg = None;
+    let mut alpha = Fr::one();
+    for tau_i in &tau {
+        let t = IPForMLSumcheck::prove_zerocheck_round(&mut zerocheck_state, &verifier_msg);
+        let s = IPForMLSumcheck::prove_round(&mut sumcheck_state, &verifier_msg);
+        assert_eq!(t.evaluations().len(), degree + 1);
+        assert_eq!(s.evaluations().len(), degree + 2);
+        for (x, s_x) in s.evaluations().iter().enumerate() {
+            let x = Fr::from(x as u64);
+            let t_x = interpolate_uni_poly(t.evaluations(), x);
+            let eq_x = *tau_i * x + (Fr::one() - tau_i) * (Fr::one() - x);
+            assert_eq!(*s_x, alpha * eq_x * t_x);
+        }
+        let r = Fr::rand(&mut rng);
+        alpha *= *tau_i * r + (Fr::one() - tau_i) * (Fr::one() - r);
+        verifier_msg = Some(VerifierMsg { randomness: r });
+    }
+}
+
+#[test]
+fn test_zerocheck_rejects() {
+    let nv = 6;
+    let mut rng = test_rng();
+
+    // a polynomial that does not vanish is rejected in the first round
+    let (poly, _) = random_list_of_products::<Fr, _>(nv, (2, 4), 3, &mut rng);
+    let proof = MLSumcheck::prove_zerocheck(&poly).unwrap();
+    match MLSumcheck::verify_zerocheck(&poly.info(), &proof) {
+        Err(crate::Error::Reject(Some(msg))) => assert!(msg.starts_with("Round 0:"), "{}", msg),
+        _ => panic!("non-vanishing polynomial accepted"),
+    }
+
+    let poly = vanishing_polynomial(nv, &mut rng);
+    let info = poly.info();
+    let proof = MLSumcheck::prove_zerocheck(&poly).unwrap();
+
+    // changing t_i(2) keeps the check of the round, and is caught by the final subclaim
+    let mut tampered = proof.clone();
+    tampered[nv - 1].evaluations[2] += Fr::one();
+    let subclaim = MLSumcheck::verify_zerocheck(&info, &tampered).unwrap();
+    assert_ne!(poly.evaluate(&subclaim.point), subclaim.expected_evaluation);
+
+    let mut tampered = proof.clone();
+    tampered[2].evaluations[0] += Fr::one();
+    assert_rejected(MLSumcheck::verify_zerocheck(&info, &tampered), "tampered");
+
+    let mut truncated = proof.clone();
+    truncated.pop();
+    assert_rejected(MLSumcheck::verify_zerocheck(&info, &truncated), "truncated");
+
+    let mut extra = proof.clone();
+    extra.push(proof[0].clone());
+    assert_rejected(MLSumcheck::verify_zerocheck(&info, &extra), "extra message");
+
+    let mut high_degree = proof;
+    high_degree[1].evaluations.push(Fr::one());
+    assert_rejected(
+        MLSumcheck::verify_zerocheck(&info, &high_degree),
+        "high degree",
+    );
+}
+
+#[test]
+fn test_batch_sumcheck() {
+    let mut rng = test_rng();
+    let mut polynomials: Vec<_> = [(6, (2, 3)), (3, (1, 2)), (8, (2, 4)), (1, (3, 4))]
+        .iter()
+        .map(|&(nv, range)| random_list_of_products::<Fr, _>(nv, range, 2, &mut rng))
+        .collect();
+    // a constant polynomial has no variables
+    let constant = Fr::rand(&mut rng);
+    let mut poly = ListOfProductsOfPolynomials::new(0);
+    poly.add_product(
+        vec![Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+            0,
+            vec![constant],
+        ))],
+        Fr::one(),
+    );
+    polynomials.push((poly, constant));
+    let (polynomials, sums): (Vec<_>, Vec<_>) = polynomials.into_iter().unzip();
+    let infos: Vec<_> = polynomials.iter().map(|p| p.info()).collect();
+
+    let (proof, claimed_sums) = MLSumcheck::prove_batch(&polynomials).unwrap();
+    assert_eq!(claimed_sums, sums);
+    assert_eq!(proof.sumcheck_proof.len(), 8);
+    let degree = infos
+        .iter()
+        .map(|info| info.max_multiplicands)
+        .max()
+        .unwrap();
+    assert!(proof
+        .sumcheck_proof
+        .iter()
+        .all(|msg| msg.evaluations().len() == degree + 1));
+
+    let subclaims = MLSumcheck::verify_batch(&infos, &sums, &proof).unwrap();
+    assert_eq!(subclaims.len(), polynomials.len());
+    let point = &subclaims[2].point;
+    for (poly, subclaim) in polynomials.iter().zip(&subclaims) {
+        assert_eq!(subclaim.point.len(), poly.num_variables);
+        assert_eq!(subclaim.point[..], point[8 - poly.num_variables..]);
+        assert_eq!(poly.evaluate(&subclaim.point), subclaim.expected_evaluation);
+    }
+
+    let mut fs_rng = Blake2b512Rng::setup();
+    let (_, prover_state) =
+        MLSumcheck::prove_batch_as_subprotocol(&mut fs_rng, &polynomials).unwrap();
+    assert_eq!(&prover_state.randomness, point);
+
+    // a batch of one polynomial
+    let (proof, claimed_sums) = MLSumcheck::prove_batch(&polynomials[..1]).unwrap();
+    let subclaims = MLSumcheck::verify_batch(&infos[..1], &claimed_sums, &proof).unwrap();
+    assert_eq!(
+        polynomials[0].evaluate(&subclaims[0].point),
+        subclaims[0].expected_evaluation
+    );
+}
+
+#[test]
+fn test_batch_sumcheck_rejects() {
+    let mut rng = test_rng();
+    let (polynomials, sums): (Vec<_>, Vec<_>) = [(5, (2, 3)), (2, (1, 2)), (4, (2, 3))]
+        .iter()
+        .map(|&(nv, range)| random_list_of_products::<Fr, _>(nv, range, 2, &mut rng))
+        .unzip();
+    let infos: Vec<_> = polynomials.iter().map(|p| p.info()).collect();
+    let (proof, _) = MLSumcheck::prove_batch(&polynomials).unwrap();
+    assert!(MLSumcheck::verify_batch(&infos, &sums, &proof).is_ok());
+
+    let mut wrong_sums = sums.clone();
+    wrong_sums[1] += Fr::one();
+    assert_rejected(
+        MLSumcheck::verify_batch(&infos, &wrong_sums, &proof),
+        "wrong sum",
+    );
+
+    let mut wrong_evaluation = proof.clone();
+    wrong_evaluation.evaluations[2] += Fr::one();
+    assert_rejected(
+        MLSumcheck::verify_batch(&infos, &sums, &wrong_evaluation),
+        "wrong evaluation",
+    );
+
+    let mut missing_evaluation = proof.clone();
+    missing_evaluation.evaluations.pop();
+    assert_rejected(
+        MLSumcheck::verify_batch(&infos, &sums, &missing_evaluation),
+        "missing evaluation",
+    );
+
+    let mut truncated = proof.clone();
+    truncated.sumcheck_proof.pop();
+    assert_rejected(
+        MLSumcheck::verify_batch(&infos, &sums, &truncated),
+        "truncated",
+    );
+
+    assert!(MLSumcheck::verify_batch(&infos, &sums[..2], &proof).is_err());
+    assert!(MLSumcheck::<Fr>::prove_batch(&[]).is_err());
+}
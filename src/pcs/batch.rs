@@ -0,0 +1,211 @@
+//! Batched openings of several committed polynomials.
+//!
+//! Claims `f_i(z) = v_i` at a shared point are batched with a random linear combination: the
+//! verifier samples `c_i` from the transcript, and the prover opens `sum_i c_i f_i` at `z`, which
+//! is checked against the combined commitment and `sum_i c_i v_i`.
+//!
+//! Claims `f_i(z_i) = v_i` at different points are first reduced to a single point `r` with the
+//! sumcheck of `sum_i c_i v_i = sum_x sum_i c_i eq(z_i, x) f_i(x)`, after which the prover opens
+//! `sum_i c_i eq(z_i, r) f_i` at `r`.
+
+use crate::ml_sumcheck::protocol::{ListOfProductsOfPolynomials, PolynomialInfo};
+use crate::ml_sumcheck::{MLSumcheck, Proof};
+use crate::pcs::HomomorphicPCS;
+use crate::rng::FeedableRNG;
+use crate::utils::{eq_eval, eq_evaluations};
+use ark_ff::Field;
+use ark_poly::{DenseMultilinearExtension, Polynomial};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::sync::Arc;
+use ark_std::vec::Vec;
+
+/// Proof of a batched opening at several points
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct MultiPointProof<F: Field, Q: CanonicalSerialize + CanonicalDeserialize> {
+    /// sumcheck reducing the claims to a single point
+    pub sumcheck_proof: Proof<F>,
+    /// opening of the combined polynomial at the point of the sumcheck
+    pub opening: Q,
+}
+
+/// Returns `sum_i coefficients[i] * polys[i]`.
+fn combine_polynomials<F: Field>(
+    polys: &[DenseMultilinearExtension<F>],
+    coefficients: &[F],
+) -> DenseMultilinearExtension<F> {
+    let num_vars = polys[0].num_vars;
+    let mut evaluations = vec![F::zero(); 1 << num_vars];
+    for (poly, c) in polys.iter().zip(coefficients) {
+        for (acc, x) in evaluations.iter_mut().zip(&poly.evaluations) {
+            *acc += *c * x;
+        }
+    }
+    DenseMultilinearExtension::from_evaluations_vec(num_vars, evaluations)
+}
+
+/// Checks that the polynomials are non-empty and have `num_vars` variables.
+fn check_polynomials<F: Field>(
+    polys: &[DenseMultilinearExtension<F>],
+    num_commitments: usize,
+    num_vars: usize,
+) -> Result<(), crate::Error> {
+    if polys.is_empty() || polys.len() != num_commitments {
+        return Err(crate::Error::OtherError(
+            "There must be one commitment per polynomial.".into(),
+        ));
+    }
+    if polys.iter().any(|p| p.num_vars != num_vars) {
+        return Err(crate::Error::OtherError(
+            "Polynomials have different numbers of variables.".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Feeds the claims to the transcript, and returns the coefficients of the combination.
+fn batch_coefficients<F: Field, C: CanonicalSerialize>(
+    fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+    commitments: &[C],
+    points: &[Vec<F>],
+    values: &[F],
+) -> Result<Vec<F>, crate::Error> {
+    for commitment in commitments {
+        fs_rng.feed(commitment)?;
+    }
+    for point in points {
+        fs_rng.feed(point)?;
+    }
+    fs_rng.feed(&values.to_vec())?;
+    Ok((0..commitments.len()).map(|_| F::rand(fs_rng)).collect())
+}
+
+/// Proves the evaluations of the committed polynomials at `point` with a single opening.
+pub fn batch_open<F: Field, P: HomomorphicPCS<F>>(
+    fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+    pp: &P::Parameters,
+    polys: &[DenseMultilinearExtension<F>],
+    commitments: &[P::Commitment],
+    point: &[F],
+) -> Result<P::Proof, crate::Error> {
+    check_polynomials(polys, commitments.len(), point.len())?;
+    let values: Vec<_> = polys.iter().map(|p| p.evaluate(&point.to_vec())).collect();
+    let coefficients = batch_coefficients(fs_rng, commitments, &[point.to_vec()], &values)?;
+    P::open(pp, &combine_polynomials(polys, &coefficients), point)
+}
+
+/// Verifies that the polynomial committed in `commitments[i]` evaluates to `values[i]` at
+/// `point`.
+pub fn batch_verify<F: Field, P: HomomorphicPCS<F>>(
+    fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+    pp: &P::Parameters,
+    commitments: &[P::Commitment],
+    point: &[F],
+    values: &[F],
+    proof: &P::Proof,
+) -> Result<bool, crate::Error> {
+    if commitments.is_empty() || commitments.len() != values.len() {
+        return Err(crate::Error::Reject(Some(
+            "There must be one value per commitment.".into(),
+        )));
+    }
+    let coefficients = batch_coefficients(fs_rng, commitments, &[point.to_vec()], values)?;
+    let commitment = P::combine_commitments(commitments, &coefficients)?;
+    let value = coefficients.iter().zip(values).map(|(c, v)| *c * v).sum();
+    P::verify(pp, &commitment, point, value, proof)
+}
+
+/// Proves the evaluation of the polynomial committed in `commitments[i]` at `points[i]`, with a
+/// sumcheck reducing the claims to a single point and a single opening.
+pub fn multi_point_open<F: Field, P: HomomorphicPCS<F>>(
+    fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+    pp: &P::Parameters,
+    polys: &[DenseMultilinearExtension<F>],
+    commitments: &[P::Commitment],
+    points: &[Vec<F>],
+) -> Result<MultiPointProof<F, P::Proof>, crate::Error> {
+    let num_vars = points.first().map_or(0, |z| z.len());
+    check_polynomials(polys, commitments.len(), num_vars)?;
+    if points.len() != polys.len() || points.iter().any(|z| z.len() != num_vars) {
+        return Err(crate::Error::OtherError(
+            "There must be one point per polynomial.".into(),
+        ));
+    }
+    let values: Vec<_> = polys
+        .iter()
+        .zip(points)
+        .map(|(p, z)| p.evaluate(z))
+        .collect();
+    let coefficients = batch_coefficients(fs_rng, commitments, points, &values)?;
+
+    let (sumcheck_proof, r) = if num_vars == 0 {
+        (Vec::new(), Vec::new())
+    } else {
+        let mut polynomial = ListOfProductsOfPolynomials::new(num_vars);
+        for ((poly, z), c) in polys.iter().zip(points).zip(&coefficients) {
+            let eq = DenseMultilinearExtension::from_evaluations_vec(num_vars, eq_evaluations(z));
+            polynomial.add_product(vec![Arc::new(eq), Arc::new(poly.clone())], *c);
+        }
+        let (proof, state) = MLSumcheck::prove_as_subprotocol(fs_rng, &polynomial)?;
+        (proof, state.randomness)
+    };
+
+    let reduced_coefficients: Vec<_> = coefficients
+        .iter()
+        .zip(points)
+        .map(|(c, z)| *c * eq_eval(z, &r))
+        .collect();
+    let opening = P::open(pp, &combine_polynomials(polys, &reduced_coefficients), &r)?;
+    Ok(MultiPointProof {
+        sumcheck_proof,
+        opening,
+    })
+}
+
+/// Verifies that the polynomial committed in `commitments[i]` evaluates to `values[i]` at
+/// `points[i]`.
+pub fn multi_point_verify<F: Field, P: HomomorphicPCS<F>>(
+    fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+    pp: &P::Parameters,
+    commitments: &[P::Commitment],
+    points: &[Vec<F>],
+    values: &[F],
+    proof: &MultiPointProof<F, P::Proof>,
+) -> Result<bool, crate::Error> {
+    let num_vars = points.first().map_or(0, |z| z.len());
+    if commitments.is_empty()
+        || commitments.len() != values.len()
+        || commitments.len() != points.len()
+        || points.iter().any(|z| z.len() != num_vars)
+    {
+        return Err(crate::Error::Reject(Some(
+            "There must be one point and one value per commitment.".into(),
+        )));
+    }
+    if proof.sumcheck_proof.len() != num_vars {
+        return Err(crate::Error::Reject(Some(
+            "Sumcheck proof has the wrong number of rounds.".into(),
+        )));
+    }
+    let coefficients = batch_coefficients(fs_rng, commitments, points, values)?;
+    let claimed_sum = coefficients.iter().zip(values).map(|(c, v)| *c * v).sum();
+
+    let (r, expected_evaluation) = if num_vars == 0 {
+        (Vec::new(), claimed_sum)
+    } else {
+        let info = PolynomialInfo {
+            max_multiplicands: 2,
+            num_variables: num_vars,
+        };
+        let subclaim =
+            MLSumcheck::verify_as_subprotocol(fs_rng, &info, claimed_sum, &proof.sumcheck_proof)?;
+        (subclaim.point, subclaim.expected_evaluation)
+    };
+
+    let reduced_coefficients: Vec<_> = coefficients
+        .iter()
+        .zip(points)
+        .map(|(c, z)| *c * eq_eval(z, &r))
+        .collect();
+    let commitment = P::combine_commitments(commitments, &reduced_coefficients)?;
+    P::verify(pp, &commitment, &r, expected_evaluation, &proof.opening)
+}
@@ -0,0 +1,303 @@
+//! Hyrax commitment of [WTSTW18](https://eprint.iacr.org/2017/1132) for multilinear polynomials.
+//!
+//! The `2^n` evaluations of the polynomial are arranged in a matrix `M` whose columns are indexed
+//! by the low variables, so that `f(r) = eq(r_high)^T * M * eq(r_low)`. The commitment is a vector
+//! Pedersen commitment to each row of `M`. To open `f` at `r`, the verifier combines the row
+//! commitments by `eq(r_high)` into a commitment to `a = eq(r_high)^T * M`, and the prover shows
+//! `<a, eq(r_low)> = f(r)` with the inner product argument of
+//! [BCCGP16](https://eprint.iacr.org/2016/263) and [BBBPWM18](https://eprint.iacr.org/2017/1066).
+//!
+//! Commitments have `2^(n/2)` group elements and proofs have `O(n)` group elements. The generators
+//! are sampled from a public seed, so there is no trusted setup.
+
+use crate::pcs::{
+    combine_rows, common_num_vars, inner_product, matrix_dimensions, HomomorphicPCS, MultilinearPCS,
+};
+use crate::rng::{Blake2b512Rng, FeedableRNG};
+use crate::utils::eq_evaluations;
+use ark_ec::CurveGroup;
+use ark_ff::{batch_inversion, Field, One, UniformRand, Zero};
+use ark_poly::DenseMultilinearExtension;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::marker::PhantomData;
+use ark_std::rand::RngCore;
+use ark_std::vec::Vec;
+
+/// Public generators of the Hyrax commitment
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct HyraxParameters<G: CurveGroup> {
+    /// maximum number of variables of committed polynomials
+    pub num_vars: usize,
+    /// generators of the row commitments
+    pub generators: Vec<G::Affine>,
+    /// generator binding the inner product in the inner product argument
+    pub u: G::Affine,
+}
+
+impl<G: CurveGroup> HyraxParameters<G> {
+    /// Derives the generators for polynomials in at most `num_vars` variables from a public
+    /// seed, so that nobody knows their discrete logarithms.
+    pub fn new(num_vars: usize) -> Result<Self, crate::Error> {
+        let mut rng = Blake2b512Rng::setup();
+        rng.feed(&b"Hyrax generators".to_vec())?;
+        rng.feed(&(num_vars as u64))?;
+        let (_, col_vars) = matrix_dimensions(num_vars);
+        let generators: Vec<_> = (0..1 << col_vars).map(|_| G::rand(&mut rng)).collect();
+        Ok(Self {
+            num_vars,
+            generators: G::normalize_batch(&generators),
+            u: G::rand(&mut rng).into_affine(),
+        })
+    }
+}
+
+/// Pedersen commitments to the rows of the evaluation matrix
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct HyraxCommitment<G: CurveGroup> {
+    /// number of variables of the committed polynomial
+    pub num_vars: usize,
+    /// commitments to the rows
+    pub row_commitments: Vec<G::Affine>,
+}
+
+/// Inner product argument opening a Hyrax commitment
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct HyraxProof<G: CurveGroup> {
+    /// cross terms `L_j` of each round
+    pub left: Vec<G::Affine>,
+    /// cross terms `R_j` of each round
+    pub right: Vec<G::Affine>,
+    /// the folded vector, of length one
+    pub a: G::ScalarField,
+}
+
+/// Hyrax commitment with an inner product argument, generic over the group
+pub struct Hyrax<G: CurveGroup> {
+    _marker: PhantomData<G>,
+}
+
+/// Starts the transcript of an opening, and returns the challenge scaling `u`.
+fn start_transcript<G: CurveGroup>(
+    commitment: &HyraxCommitment<G>,
+    point: &[G::ScalarField],
+    value: G::ScalarField,
+) -> Result<(Blake2b512Rng, G::ScalarField), crate::Error> {
+    let mut fs_rng = Blake2b512Rng::setup();
+    fs_rng.feed(commitment)?;
+    fs_rng.feed(&point.to_vec())?;
+    fs_rng.feed(&value)?;
+    let x = G::ScalarField::rand(&mut fs_rng);
+    Ok((fs_rng, x))
+}
+
+/// Feeds the cross terms of a round, and returns its non-zero challenge.
+fn round_challenge<G: CurveGroup>(
+    fs_rng: &mut Blake2b512Rng,
+    left: &G::Affine,
+    right: &G::Affine,
+) -> Result<G::ScalarField, crate::Error> {
+    fs_rng.feed(left)?;
+    fs_rng.feed(right)?;
+    let challenge = G::ScalarField::rand(fs_rng);
+    if challenge.is_zero() {
+        return Err(crate::Error::OtherError("Zero challenge.".into()));
+    }
+    Ok(challenge)
+}
+
+impl<G: CurveGroup> Hyrax<G> {
+    /// Same as `open`, but takes the commitment to `poly` instead of recomputing it.
+    pub fn open_with_commitment(
+        pp: &HyraxParameters<G>,
+        poly: &DenseMultilinearExtension<G::ScalarField>,
+        commitment: &HyraxCommitment<G>,
+        point: &[G::ScalarField],
+    ) -> Result<HyraxProof<G>, crate::Error> {
+        if poly.num_vars > pp.num_vars {
+            return Err(crate::Error::OtherError(
+                "Polynomial has too many variables for the parameters.".into(),
+            ));
+        }
+        if point.len() != poly.num_vars {
+            return Err(crate::Error::OtherError(
+                "Point has the wrong number of variables.".into(),
+            ));
+        }
+        if commitment.num_vars != poly.num_vars {
+            return Err(crate::Error::OtherError(
+                "Commitment has the wrong number of variables.".into(),
+            ));
+        }
+        let (_, col_vars) = matrix_dimensions(poly.num_vars);
+        let mut a = combine_rows(
+            &eq_evaluations(&point[col_vars..]),
+            &poly.evaluations,
+            1 << col_vars,
+        );
+        let mut b = eq_evaluations(&point[..col_vars]);
+        let value = inner_product(&a, &b);
+        let (mut fs_rng, x) = start_transcript(commitment, point, value)?;
+        let u = pp.u * x;
+
+        let mut g: Vec<G> = pp.generators[..1 << col_vars]
+            .iter()
+            .map(|g| (*g).into())
+            .collect();
+        let mut left = Vec::with_capacity(col_vars);
+        let mut right = Vec::with_capacity(col_vars);
+        while a.len() > 1 {
+            let half = a.len() / 2;
+            let (a_l, a_r) = a.split_at(half);
+            let (b_l, b_r) = b.split_at(half);
+            let (g_l, g_r) = g.split_at(half);
+            let g_l = G::normalize_batch(g_l);
+            let g_r = G::normalize_batch(g_r);
+            let l = (G::msm_unchecked(&g_r, a_l) + u * inner_product(a_l, b_r)).into_affine();
+            let r = (G::msm_unchecked(&g_l, a_r) + u * inner_product(a_r, b_l)).into_affine();
+            let c = round_challenge::<G>(&mut fs_rng, &l, &r)?;
+            let c_inv = c.inverse().unwrap();
+            a = (0..half).map(|i| c * a_l[i] + c_inv * a_r[i]).collect();
+            b = (0..half).map(|i| c_inv * b_l[i] + c * b_r[i]).collect();
+            g = (0..half).map(|i| g_l[i] * c_inv + g_r[i] * c).collect();
+            left.push(l);
+            right.push(r);
+        }
+        Ok(HyraxProof {
+            left,
+            right,
+            a: a[0],
+        })
+    }
+}
+
+impl<G: CurveGroup> MultilinearPCS<G::ScalarField> for Hyrax<G> {
+    type Parameters = HyraxParameters<G>;
+    type Commitment = HyraxCommitment<G>;
+    type Proof = HyraxProof<G>;
+
+    /// Derives the generators with `HyraxParameters::new`. `rng` is not used.
+    fn setup<R: RngCore>(num_vars: usize, _rng: &mut R) -> Result<Self::Parameters, crate::Error> {
+        HyraxParameters::new(num_vars)
+    }
+
+    fn commit(
+        pp: &Self::Parameters,
+        poly: &DenseMultilinearExtension<G::ScalarField>,
+    ) -> Result<Self::Commitment, crate::Error> {
+        if poly.num_vars > pp.num_vars {
+            return Err(crate::Error::OtherError(
+                "Polynomial has too many variables for the parameters.".into(),
+            ));
+        }
+        let (_, col_vars) = matrix_dimensions(poly.num_vars);
+        let generators = &pp.generators[..1 << col_vars];
+        let row_commitments: Vec<_> = poly
+            .evaluations
+            .chunks(1 << col_vars)
+            .map(|row| G::msm_unchecked(generators, row))
+            .collect();
+        Ok(HyraxCommitment {
+            num_vars: poly.num_vars,
+            row_commitments: G::normalize_batch(&row_commitments),
+        })
+    }
+
+    /// Opens the polynomial at `point`. The prover does not keep state between `commit` and
+    /// `open`, so the commitment that seeds the transcript is recomputed, which costs as much as
+    /// `commit`. Use `Hyrax::open_with_commitment` when the commitment is at hand.
+    fn open(
+        pp: &Self::Parameters,
+        poly: &DenseMultilinearExtension<G::ScalarField>,
+        point: &[G::ScalarField],
+    ) -> Result<Self::Proof, crate::Error> {
+        let commitment = Self::commit(pp, poly)?;
+        Self::open_with_commitment(pp, poly, &commitment, point)
+    }
+
+    fn verify(
+        pp: &Self::Parameters,
+        commitment: &Self::Commitment,
+        point: &[G::ScalarField],
+        value: G::ScalarField,
+        proof: &Self::Proof,
+    ) -> Result<bool, crate::Error> {
+        let reject = |msg: &str| Err(crate::Error::Reject(Some(msg.into())));
+        if commitment.num_vars != point.len() || commitment.num_vars > pp.num_vars {
+            return reject("Point has the wrong number of variables.");
+        }
+        let (row_vars, col_vars) = matrix_dimensions(commitment.num_vars);
+        if commitment.row_commitments.len() != 1 << row_vars {
+            return reject("Commitment has the wrong number of rows.");
+        }
+        if proof.left.len() != col_vars || proof.right.len() != col_vars {
+            return reject("Proof has the wrong number of rounds.");
+        }
+
+        let (mut fs_rng, x) = start_transcript(commitment, point, value)?;
+        let u = pp.u * x;
+        let challenges = proof
+            .left
+            .iter()
+            .zip(&proof.right)
+            .map(|(l, r)| round_challenge::<G>(&mut fs_rng, l, r))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut inverses = challenges.clone();
+        batch_inversion(&mut inverses);
+
+        // P = <a, g> + <a, b> u, where the commitment to `a` combines the rows
+        let p = G::msm_unchecked(
+            &commitment.row_commitments,
+            &eq_evaluations(&point[col_vars..]),
+        ) + u * value;
+        // P' = sum_j c_j^2 L_j + P + sum_j c_j^-2 R_j
+        let squares: Vec<_> = challenges
+            .iter()
+            .chain(&inverses)
+            .map(|c| c.square())
+            .collect();
+        let cross_terms: Vec<_> = proof.left.iter().chain(&proof.right).copied().collect();
+        let folded_p = p + G::msm_unchecked(&cross_terms, &squares);
+
+        // the folded g and b are <s, g> and <s, b>, where s_i is the product of c_j^-1 or c_j
+        // for each round j, depending on whether i is in the left or right half in round j
+        let mut s = vec![G::ScalarField::one()];
+        for (c, c_inv) in challenges.iter().zip(&inverses).rev() {
+            s = s
+                .iter()
+                .map(|x| *x * c_inv)
+                .chain(s.iter().map(|x| *x * c))
+                .collect();
+        }
+        let folded_g = G::msm_unchecked(&pp.generators[..1 << col_vars], &s);
+        let folded_b = inner_product(&s, &eq_evaluations(&point[..col_vars]));
+        Ok(folded_p == folded_g * proof.a + u * (proof.a * folded_b))
+    }
+}
+
+impl<G: CurveGroup> HomomorphicPCS<G::ScalarField> for Hyrax<G> {
+    fn combine_commitments(
+        commitments: &[Self::Commitment],
+        coefficients: &[G::ScalarField],
+    ) -> Result<Self::Commitment, crate::Error> {
+        let num_vars = common_num_vars(commitments, coefficients, |c| c.num_vars)?;
+        let (row_vars, _) = matrix_dimensions(num_vars);
+        if commitments
+            .iter()
+            .any(|c| c.row_commitments.len() != 1 << row_vars)
+        {
+            return Err(crate::Error::Reject(Some(
+                "Commitment has the wrong number of rows.".into(),
+            )));
+        }
+        let row_commitments: Vec<_> = (0..1 << row_vars)
+            .map(|i| {
+                let rows: Vec<_> = commitments.iter().map(|c| c.row_commitments[i]).collect();
+                G::msm_unchecked(&rows, coefficients)
+            })
+            .collect();
+        Ok(HyraxCommitment {
+            num_vars,
+            row_commitments: G::normalize_batch(&row_commitments),
+        })
+    }
+}
@@ -0,0 +1,202 @@
+//! Multilinear KZG commitment of [PST13](https://eprint.iacr.org/2011/587).
+//!
+//! The commitment to `f` is `f(τ)·G` for a secret point `τ`, computed from the evaluations of `f`
+//! on the hypercube and the group elements `eq(τ, b)·G`. To open `f` at `z`, the prover writes
+//! `f(x) - f(z) = sum_i (x_i - z_i) q_i(x_{i+1}, ..., x_{n-1})` and commits to each quotient
+//! `q_i`. The verifier checks `e(C - f(z)·G, H) = prod_i e(π_i, (τ_i - z_i)·H)`.
+//!
+//! Commitments and proofs have a size independent of, and logarithmic in, the size of the
+//! polynomial respectively.
+
+use crate::pcs::{common_num_vars, HomomorphicPCS, MultilinearPCS};
+use crate::utils::eq_evaluations;
+use ark_ec::pairing::Pairing;
+use ark_ec::scalar_mul::ScalarMul;
+use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{UniformRand, Zero};
+use ark_poly::DenseMultilinearExtension;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::marker::PhantomData;
+use ark_std::rand::RngCore;
+use ark_std::vec::Vec;
+
+/// Structured reference string of the multilinear KZG commitment
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct MultilinearKZGParameters<E: Pairing> {
+    /// maximum number of variables of committed polynomials
+    pub num_vars: usize,
+    /// `powers[k][b] = eq(τ_k, ..., τ_{n-1}, b)·G`, for polynomials in the last `n - k` variables
+    pub powers: Vec<Vec<E::G1Affine>>,
+    /// generator of G1
+    pub g: E::G1Affine,
+    /// generator of G2
+    pub h: E::G2Affine,
+    /// `τ_i·H`
+    pub h_tau: Vec<E::G2Affine>,
+}
+
+impl<E: Pairing> MultilinearKZGParameters<E> {
+    /// Generates parameters from a random `τ`, which is known while generating them.
+    ///
+    /// This is only suitable for tests: anyone who learns `τ` can open commitments to any value,
+    /// so real deployments need parameters from a trusted setup ceremony.
+    pub fn setup_for_testing<R: RngCore>(num_vars: usize, rng: &mut R) -> Self {
+        let tau: Vec<_> = (0..num_vars).map(|_| E::ScalarField::rand(rng)).collect();
+        let g = E::G1::rand(rng);
+        let h = E::G2::rand(rng);
+        let powers = (0..=num_vars)
+            .map(|k| g.batch_mul(&eq_evaluations(&tau[k..])))
+            .collect();
+        Self {
+            num_vars,
+            powers,
+            g: g.into_affine(),
+            h: h.into_affine(),
+            h_tau: h.batch_mul(&tau),
+        }
+    }
+}
+
+/// Commitment `f(τ)·G` of a polynomial
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct MultilinearKZGCommitment<E: Pairing> {
+    /// number of variables of the committed polynomial
+    pub num_vars: usize,
+    /// the commitment
+    pub commitment: E::G1Affine,
+}
+
+/// Opening proof of the multilinear KZG commitment
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct MultilinearKZGProof<E: Pairing> {
+    /// commitments to the quotients, one per variable
+    pub quotients: Vec<E::G1Affine>,
+}
+
+/// Multilinear KZG commitment, generic over the pairing
+pub struct MultilinearKZG<E: Pairing> {
+    _marker: PhantomData<E>,
+}
+
+/// A polynomial in `nv` variables is committed in the last `nv` variables of `τ`.
+fn offset<E: Pairing>(
+    pp: &MultilinearKZGParameters<E>,
+    num_vars: usize,
+) -> Result<usize, crate::Error> {
+    pp.num_vars.checked_sub(num_vars).ok_or_else(|| {
+        crate::Error::OtherError("Polynomial has too many variables for the parameters.".into())
+    })
+}
+
+impl<E: Pairing> MultilinearPCS<E::ScalarField> for MultilinearKZG<E> {
+    type Parameters = MultilinearKZGParameters<E>;
+    type Commitment = MultilinearKZGCommitment<E>;
+    type Proof = MultilinearKZGProof<E>;
+
+    /// Returns an error: the parameters must come from a trusted setup, as anyone who knows `τ`
+    /// can forge openings. Deserialize them from the output of a ceremony, or call
+    /// `MultilinearKZGParameters::setup_for_testing` explicitly in tests.
+    ///
+    /// Unit tests of this crate get `setup_for_testing`, so that tests generic over
+    /// `MultilinearPCS` cover this scheme.
+    #[cfg(not(test))]
+    fn setup<R: RngCore>(_num_vars: usize, _rng: &mut R) -> Result<Self::Parameters, crate::Error> {
+        Err(crate::Error::OtherError(
+            "Multilinear KZG needs parameters from a trusted setup.".into(),
+        ))
+    }
+
+    #[cfg(test)]
+    fn setup<R: RngCore>(num_vars: usize, rng: &mut R) -> Result<Self::Parameters, crate::Error> {
+        Ok(MultilinearKZGParameters::setup_for_testing(num_vars, rng))
+    }
+
+    fn commit(
+        pp: &Self::Parameters,
+        poly: &DenseMultilinearExtension<E::ScalarField>,
+    ) -> Result<Self::Commitment, crate::Error> {
+        let k = offset(pp, poly.num_vars)?;
+        Ok(MultilinearKZGCommitment {
+            num_vars: poly.num_vars,
+            commitment: E::G1::msm_unchecked(&pp.powers[k], &poly.evaluations).into_affine(),
+        })
+    }
+
+    fn open(
+        pp: &Self::Parameters,
+        poly: &DenseMultilinearExtension<E::ScalarField>,
+        point: &[E::ScalarField],
+    ) -> Result<Self::Proof, crate::Error> {
+        if point.len() != poly.num_vars {
+            return Err(crate::Error::OtherError(
+                "Point has the wrong number of variables.".into(),
+            ));
+        }
+        let k = offset(pp, poly.num_vars)?;
+        let mut table = poly.evaluations.clone();
+        let mut quotients = Vec::with_capacity(poly.num_vars);
+        for (i, z) in point.iter().enumerate() {
+            // q_i(rest) = f(1, rest) - f(0, rest), and f is fixed to f(z_i, rest)
+            let half = table.len() / 2;
+            let mut quotient = Vec::with_capacity(half);
+            for j in 0..half {
+                let (low, high) = (table[2 * j], table[2 * j + 1]);
+                quotient.push(high - low);
+                table[j] = low + *z * (high - low);
+            }
+            table.truncate(half);
+            quotients.push(E::G1::msm_unchecked(&pp.powers[k + i + 1], &quotient));
+        }
+        Ok(MultilinearKZGProof {
+            quotients: E::G1::normalize_batch(&quotients),
+        })
+    }
+
+    fn verify(
+        pp: &Self::Parameters,
+        commitment: &Self::Commitment,
+        point: &[E::ScalarField],
+        value: E::ScalarField,
+        proof: &Self::Proof,
+    ) -> Result<bool, crate::Error> {
+        let num_vars = commitment.num_vars;
+        if point.len() != num_vars || proof.quotients.len() != num_vars {
+            return Err(crate::Error::Reject(Some(
+                "Point or proof has the wrong number of variables.".into(),
+            )));
+        }
+        let k = offset(pp, num_vars)
+            .map_err(|_| crate::Error::Reject(Some("Commitment has too many variables.".into())))?;
+
+        // e(C - v·G, H) * prod_i e(-π_i, τ_i·H - z_i·H) = 1
+        let mut g1 = vec![(commitment.commitment.into_group() - pp.g * value).into_affine()];
+        g1.extend(
+            proof
+                .quotients
+                .iter()
+                .map(|q| (-q.into_group()).into_affine()),
+        );
+        let mut g2 = vec![pp.h];
+        g2.extend(
+            point
+                .iter()
+                .zip(&pp.h_tau[k..])
+                .map(|(z, h_tau)| (h_tau.into_group() - pp.h * z).into_affine()),
+        );
+        Ok(E::multi_pairing(g1, g2).is_zero())
+    }
+}
+
+impl<E: Pairing> HomomorphicPCS<E::ScalarField> for MultilinearKZG<E> {
+    fn combine_commitments(
+        commitments: &[Self::Commitment],
+        coefficients: &[E::ScalarField],
+    ) -> Result<Self::Commitment, crate::Error> {
+        let num_vars = common_num_vars(commitments, coefficients, |c| c.num_vars)?;
+        let points: Vec<_> = commitments.iter().map(|c| c.commitment).collect();
+        Ok(MultilinearKZGCommitment {
+            num_vars,
+            commitment: E::G1::msm_unchecked(&points, coefficients).into_affine(),
+        })
+    }
+}
@@ -0,0 +1,97 @@
+//! Merkle tree over Blake2b-512 digests
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::vec::Vec;
+use blake2::{Blake2b512, Digest as _};
+
+/// Output of Blake2b-512
+pub type Digest = [u8; 64];
+
+fn hash(prefix: u8, data: &[&[u8]]) -> Digest {
+    let mut hasher = Blake2b512::new();
+    hasher.update([prefix]);
+    for d in data {
+        hasher.update(d);
+    }
+    let mut digest = [0u8; 64];
+    digest.copy_from_slice(&hasher.finalize());
+    digest
+}
+
+/// Hashes a leaf. Leaves and inner nodes are hashed with different prefixes, so that a leaf cannot
+/// be passed off as an inner node.
+pub fn hash_leaf(data: &[u8]) -> Digest {
+    hash(0, &[data])
+}
+
+fn hash_node(left: &Digest, right: &Digest) -> Digest {
+    hash(1, &[left, right])
+}
+
+/// Merkle tree whose number of leaves is a power of two
+pub struct MerkleTree {
+    /// levels of the tree, from the leaves to the root
+    levels: Vec<Vec<Digest>>,
+}
+
+impl MerkleTree {
+    /// Builds the tree over the digests of the leaves.
+    pub fn new(leaves: Vec<Digest>) -> Self {
+        assert!(
+            leaves.len().is_power_of_two(),
+            "number of leaves is not a power of two"
+        );
+        let mut levels = vec![leaves];
+        while levels.last().unwrap().len() > 1 {
+            let level = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| hash_node(&pair[0], &pair[1]))
+                .collect();
+            levels.push(level);
+        }
+        Self { levels }
+    }
+
+    /// Returns the root of the tree.
+    pub fn root(&self) -> Digest {
+        self.levels.last().unwrap()[0]
+    }
+
+    /// Returns the authentication path of the leaf at `index`.
+    pub fn path(&self, mut index: usize) -> MerklePath {
+        let mut siblings = Vec::with_capacity(self.levels.len() - 1);
+        for level in &self.levels[..self.levels.len() - 1] {
+            siblings.push(level[index ^ 1]);
+            index >>= 1;
+        }
+        MerklePath { siblings }
+    }
+}
+
+/// Authentication path of a leaf, from the sibling of the leaf to the child of the root
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct MerklePath {
+    /// siblings of the nodes on the path
+    pub siblings: Vec<Digest>,
+}
+
+impl MerklePath {
+    /// Verifies that `leaf` is the leaf at `index` of the tree with root `root`.
+    pub fn verify(&self, root: &Digest, mut index: usize, leaf: &Digest) -> bool {
+        if index >> self.siblings.len() != 0 {
+            return false;
+        }
+        let mut node = *leaf;
+        for sibling in &self.siblings {
+            node = if index & 1 == 0 {
+                hash_node(&node, sibling)
+            } else {
+                hash_node(sibling, &node)
+            };
+            index >>= 1;
+        }
+        &node == root
+    }
+}
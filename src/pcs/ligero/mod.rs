@@ -0,0 +1,266 @@
+//! Ligero-style tensor commitment for multilinear polynomials, as in
+//! [AHIV17](https://eprint.iacr.org/2022/1608) and [GLSTW21](https://eprint.iacr.org/2021/1043).
+//!
+//! The `2^n` evaluations of the polynomial are arranged in a matrix `M` whose columns are indexed
+//! by the low variables and whose rows are indexed by the high variables, so that
+//! `f(r) = eq(r_high)^T * M * eq(r_low)`. Each row is encoded with a Reed-Solomon code, and the
+//! columns of the encoded matrix are committed in a Merkle tree.
+//!
+//! To open the polynomial at `r`, the prover sends a random combination of the rows for the
+//! proximity test, and the combination `eq(r_high)^T * M`. The verifier checks both against the
+//! same combinations of a few opened columns of the encoded matrix. Challenges are derived from a
+//! `FeedableRNG` transcript of the commitment and the point.
+
+pub mod merkle;
+
+use crate::pcs::ligero::merkle::{hash_leaf, Digest, MerklePath, MerkleTree};
+use crate::pcs::{combine_rows, inner_product, matrix_dimensions, MultilinearPCS};
+use crate::rng::{Blake2b512Rng, FeedableRNG};
+use crate::utils::eq_evaluations;
+use ark_ff::FftField;
+use ark_poly::{DenseMultilinearExtension, EvaluationDomain, Radix2EvaluationDomain};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::marker::PhantomData;
+use ark_std::rand::{Rng, RngCore};
+use ark_std::vec::Vec;
+use ark_std::{cfg_chunks, cfg_iter};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Default `log2` of the inverse rate of the Reed-Solomon code
+pub const DEFAULT_LOG_BLOWUP: usize = 2;
+/// Default number of opened columns
+pub const DEFAULT_NUM_QUERIES: usize = 128;
+
+/// Parameters of the Ligero commitment. There is no trusted setup.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LigeroParameters {
+    /// maximum number of variables of committed polynomials
+    pub num_vars: usize,
+    /// `log2` of the inverse rate of the Reed-Solomon code
+    pub log_blowup: usize,
+    /// number of columns opened in each proof
+    pub num_queries: usize,
+}
+
+impl LigeroParameters {
+    /// Returns parameters for polynomials in at most `num_vars` variables.
+    pub fn new(num_vars: usize, log_blowup: usize, num_queries: usize) -> Self {
+        Self {
+            num_vars,
+            log_blowup,
+            num_queries,
+        }
+    }
+}
+
+/// Merkle root of the columns of the encoded matrix
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct LigeroCommitment {
+    /// number of variables of the committed polynomial
+    pub num_vars: usize,
+    /// root of the Merkle tree
+    pub root: Digest,
+}
+
+/// Opening proof of the Ligero commitment
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct LigeroProof<F: FftField> {
+    /// random combination of the rows, for the proximity test
+    pub proximity_row: Vec<F>,
+    /// combination of the rows by `eq(r_high)`
+    pub evaluation_row: Vec<F>,
+    /// opened columns of the encoded matrix
+    pub columns: Vec<Vec<F>>,
+    /// authentication paths of the opened columns
+    pub paths: Vec<MerklePath>,
+}
+
+/// Ligero-style tensor commitment with Reed-Solomon codes and Blake2b Merkle trees
+pub struct Ligero<F: FftField> {
+    _marker: PhantomData<F>,
+}
+
+/// Encodes a row of length `2^k` as the evaluations of the polynomial with coefficients `row` on
+/// a domain of size `2^(k + log_blowup)`.
+fn encode<F: FftField>(row: &[F], log_blowup: usize) -> Result<Vec<F>, crate::Error> {
+    let domain = Radix2EvaluationDomain::<F>::new(row.len() << log_blowup).ok_or_else(|| {
+        crate::Error::OtherError("Field has no domain large enough for the encoding.".into())
+    })?;
+    Ok(domain.fft(row))
+}
+
+fn hash_column<F: FftField>(column: &[F]) -> Result<Digest, crate::Error> {
+    let mut bytes = Vec::new();
+    column.serialize_uncompressed(&mut bytes)?;
+    Ok(hash_leaf(&bytes))
+}
+
+/// Encodes the rows of the polynomial, and returns the columns of the encoded matrix with their
+/// Merkle tree.
+fn encode_and_commit<F: FftField>(
+    pp: &LigeroParameters,
+    poly: &DenseMultilinearExtension<F>,
+) -> Result<(Vec<Vec<F>>, MerkleTree), crate::Error> {
+    if poly.num_vars > pp.num_vars {
+        return Err(crate::Error::OtherError(
+            "Polynomial has too many variables for the parameters.".into(),
+        ));
+    }
+    let (_, col_vars) = matrix_dimensions(poly.num_vars);
+    let encoded_rows = cfg_chunks!(poly.evaluations, 1 << col_vars)
+        .map(|row| encode(row, pp.log_blowup))
+        .collect::<Result<Vec<_>, _>>()?;
+    let codeword_len = encoded_rows[0].len();
+    let columns: Vec<Vec<F>> = (0..codeword_len)
+        .map(|j| encoded_rows.iter().map(|row| row[j]).collect())
+        .collect();
+    let leaves = cfg_iter!(columns)
+        .map(|column| hash_column(column))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((columns, MerkleTree::new(leaves)))
+}
+
+/// Starts the transcript of an opening, and returns the challenge of the proximity test.
+fn proximity_challenge<F: FftField>(
+    fs_rng: &mut Blake2b512Rng,
+    commitment: &LigeroCommitment,
+    point: &[F],
+) -> Result<Vec<F>, crate::Error> {
+    fs_rng.feed(commitment)?;
+    fs_rng.feed(&point.to_vec())?;
+    let (row_vars, _) = matrix_dimensions(commitment.num_vars);
+    Ok((0..1 << row_vars).map(|_| F::rand(fs_rng)).collect())
+}
+
+/// Feeds the combined rows to the transcript, and returns the indices of the opened columns.
+fn query_indices<F: FftField>(
+    fs_rng: &mut Blake2b512Rng,
+    pp: &LigeroParameters,
+    proximity_row: &Vec<F>,
+    evaluation_row: &Vec<F>,
+    codeword_len: usize,
+) -> Result<Vec<usize>, crate::Error> {
+    fs_rng.feed(proximity_row)?;
+    fs_rng.feed(evaluation_row)?;
+    Ok((0..pp.num_queries)
+        .map(|_| fs_rng.gen_range(0..codeword_len))
+        .collect())
+}
+
+impl<F: FftField> MultilinearPCS<F> for Ligero<F> {
+    type Parameters = LigeroParameters;
+    type Commitment = LigeroCommitment;
+    type Proof = LigeroProof<F>;
+
+    fn setup<R: RngCore>(num_vars: usize, _rng: &mut R) -> Result<Self::Parameters, crate::Error> {
+        Ok(LigeroParameters::new(
+            num_vars,
+            DEFAULT_LOG_BLOWUP,
+            DEFAULT_NUM_QUERIES,
+        ))
+    }
+
+    fn commit(
+        pp: &Self::Parameters,
+        poly: &DenseMultilinearExtension<F>,
+    ) -> Result<Self::Commitment, crate::Error> {
+        let (_, tree) = encode_and_commit(pp, poly)?;
+        Ok(LigeroCommitment {
+            num_vars: poly.num_vars,
+            root: tree.root(),
+        })
+    }
+
+    /// Opens the polynomial at `point`. The prover does not keep state between `commit` and
+    /// `open`, so the encoded matrix is recomputed.
+    fn open(
+        pp: &Self::Parameters,
+        poly: &DenseMultilinearExtension<F>,
+        point: &[F],
+    ) -> Result<Self::Proof, crate::Error> {
+        if point.len() != poly.num_vars {
+            return Err(crate::Error::OtherError(
+                "Point has the wrong number of variables.".into(),
+            ));
+        }
+        let (columns, tree) = encode_and_commit(pp, poly)?;
+        let commitment = LigeroCommitment {
+            num_vars: poly.num_vars,
+            root: tree.root(),
+        };
+        let (_, col_vars) = matrix_dimensions(poly.num_vars);
+        let row_len = 1 << col_vars;
+
+        let mut fs_rng = Blake2b512Rng::setup();
+        let gamma = proximity_challenge(&mut fs_rng, &commitment, point)?;
+        let proximity_row = combine_rows(&gamma, &poly.evaluations, row_len);
+        let eq_high = eq_evaluations(&point[col_vars..]);
+        let evaluation_row = combine_rows(&eq_high, &poly.evaluations, row_len);
+        let indices = query_indices(
+            &mut fs_rng,
+            pp,
+            &proximity_row,
+            &evaluation_row,
+            columns.len(),
+        )?;
+
+        Ok(LigeroProof {
+            proximity_row,
+            evaluation_row,
+            columns: indices.iter().map(|&j| columns[j].clone()).collect(),
+            paths: indices.iter().map(|&j| tree.path(j)).collect(),
+        })
+    }
+
+    fn verify(
+        pp: &Self::Parameters,
+        commitment: &Self::Commitment,
+        point: &[F],
+        value: F,
+        proof: &Self::Proof,
+    ) -> Result<bool, crate::Error> {
+        let reject = |msg: &str| Err(crate::Error::Reject(Some(msg.into())));
+        if commitment.num_vars != point.len() || commitment.num_vars > pp.num_vars {
+            return reject("Point has the wrong number of variables.");
+        }
+        let (row_vars, col_vars) = matrix_dimensions(commitment.num_vars);
+        let row_len = 1 << col_vars;
+        if proof.proximity_row.len() != row_len || proof.evaluation_row.len() != row_len {
+            return reject("Combined rows have the wrong length.");
+        }
+        if proof.columns.len() != pp.num_queries || proof.paths.len() != pp.num_queries {
+            return reject("Proof has the wrong number of columns.");
+        }
+        if proof.columns.iter().any(|c| c.len() != 1 << row_vars) {
+            return reject("Opened columns have the wrong length.");
+        }
+
+        let mut fs_rng = Blake2b512Rng::setup();
+        let gamma = proximity_challenge(&mut fs_rng, commitment, point)?;
+        let encoded_proximity = encode(&proof.proximity_row, pp.log_blowup)?;
+        let encoded_evaluation = encode(&proof.evaluation_row, pp.log_blowup)?;
+        let indices = query_indices(
+            &mut fs_rng,
+            pp,
+            &proof.proximity_row,
+            &proof.evaluation_row,
+            encoded_proximity.len(),
+        )?;
+
+        let eq_high = eq_evaluations(&point[col_vars..]);
+        for ((&j, column), path) in indices.iter().zip(&proof.columns).zip(&proof.paths) {
+            if !path.verify(&commitment.root, j, &hash_column(column)?) {
+                return Ok(false);
+            }
+            if inner_product(&gamma, column) != encoded_proximity[j]
+                || inner_product(&eq_high, column) != encoded_evaluation[j]
+            {
+                return Ok(false);
+            }
+        }
+
+        let eq_low = eq_evaluations(&point[..col_vars]);
+        Ok(inner_product(&eq_low, &proof.evaluation_row) == value)
+    }
+}
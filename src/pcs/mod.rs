@@ -0,0 +1,117 @@
+//! Polynomial commitment schemes for multilinear polynomials.
+//!
+//! Protocols of this crate end with claims on evaluations of multilinear polynomials held by the
+//! prover, e.g. `V(r) = c` on the input layer of GKR. A `MultilinearPCS` lets the prover commit to
+//! such polynomials beforehand and prove their evaluations, so that the verifier can discharge the
+//! claims without reading the polynomials.
+
+pub mod batch;
+pub mod hyrax;
+pub mod kzg;
+pub mod ligero;
+#[cfg(test)]
+mod test;
+pub mod transparent;
+
+use ark_ff::Field;
+use ark_poly::DenseMultilinearExtension;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::fmt::Debug;
+use ark_std::rand::RngCore;
+use ark_std::vec::Vec;
+
+/// Polynomial commitment scheme for multilinear polynomials in evaluation form
+pub trait MultilinearPCS<F: Field> {
+    /// public parameters used by the prover and the verifier
+    type Parameters: Clone + Debug;
+    /// commitment to a polynomial
+    type Commitment: Clone + Debug + PartialEq + CanonicalSerialize + CanonicalDeserialize;
+    /// proof of an evaluation of a committed polynomial
+    type Proof: Clone + Debug + CanonicalSerialize + CanonicalDeserialize;
+
+    /// Generates the public parameters for polynomials in at most `num_vars` variables.
+    fn setup<R: RngCore>(num_vars: usize, rng: &mut R) -> Result<Self::Parameters, crate::Error>;
+
+    /// Commits to `poly`.
+    fn commit(
+        pp: &Self::Parameters,
+        poly: &DenseMultilinearExtension<F>,
+    ) -> Result<Self::Commitment, crate::Error>;
+
+    /// Proves the evaluation of `poly` at `point`.
+    fn open(
+        pp: &Self::Parameters,
+        poly: &DenseMultilinearExtension<F>,
+        point: &[F],
+    ) -> Result<Self::Proof, crate::Error>;
+
+    /// Verifies that the polynomial committed in `commitment` evaluates to `value` at `point`.
+    ///
+    /// Returns an error if the proof is malformed.
+    fn verify(
+        pp: &Self::Parameters,
+        commitment: &Self::Commitment,
+        point: &[F],
+        value: F,
+        proof: &Self::Proof,
+    ) -> Result<bool, crate::Error>;
+}
+
+/// Commitment scheme whose commitments can be combined linearly, i.e. the combination of the
+/// commitments to `f_i` is a commitment to `sum_i c_i f_i`. This allows batched openings.
+pub trait HomomorphicPCS<F: Field>: MultilinearPCS<F> {
+    /// Returns the commitment to `sum_i coefficients[i] * f_i`, where `commitments[i]` commits to
+    /// `f_i`. The polynomials must have the same number of variables, and there must be one
+    /// coefficient per commitment.
+    fn combine_commitments(
+        commitments: &[Self::Commitment],
+        coefficients: &[F],
+    ) -> Result<Self::Commitment, crate::Error>;
+}
+
+/// Returns the number of variables of the commitments, or an error if they differ, if there is
+/// none, or if there is not one coefficient per commitment.
+pub(crate) fn common_num_vars<C, F>(
+    commitments: &[C],
+    coefficients: &[F],
+    num_vars: impl Fn(&C) -> usize,
+) -> Result<usize, crate::Error> {
+    if coefficients.len() != commitments.len() {
+        return Err(crate::Error::Reject(Some(
+            "Number of coefficients does not match the number of commitments.".into(),
+        )));
+    }
+    let first = commitments
+        .first()
+        .ok_or_else(|| crate::Error::OtherError("No commitment to combine.".into()))?;
+    let nv = num_vars(first);
+    if commitments.iter().any(|c| num_vars(c) != nv) {
+        return Err(crate::Error::OtherError(
+            "Commitments have different numbers of variables.".into(),
+        ));
+    }
+    Ok(nv)
+}
+
+/// Returns the number of variables of the rows and of the columns of the matrix holding the
+/// evaluations of a polynomial in `num_vars` variables, as used by tensor-based schemes. Columns
+/// are indexed by the low variables, so that each row is a contiguous chunk of the evaluations.
+pub(crate) fn matrix_dimensions(num_vars: usize) -> (usize, usize) {
+    let col_vars = num_vars.div_ceil(2);
+    (num_vars - col_vars, col_vars)
+}
+
+/// Returns `sum_i coefficients[i] * rows[i]`, where `rows` holds the rows one after the other.
+pub(crate) fn combine_rows<F: Field>(coefficients: &[F], rows: &[F], row_len: usize) -> Vec<F> {
+    let mut combination = vec![F::zero(); row_len];
+    for (c, row) in coefficients.iter().zip(rows.chunks(row_len)) {
+        for (acc, x) in combination.iter_mut().zip(row) {
+            *acc += *c * x;
+        }
+    }
+    combination
+}
+
+pub(crate) fn inner_product<F: Field>(a: &[F], b: &[F]) -> F {
+    a.iter().zip(b).map(|(x, y)| *x * y).sum()
+}
@@ -0,0 +1,268 @@
+use crate::pcs::batch::{batch_open, batch_verify, multi_point_open, multi_point_verify};
+use crate::pcs::hyrax::Hyrax;
+use crate::pcs::kzg::MultilinearKZG;
+use crate::pcs::ligero::merkle::{hash_leaf, MerkleTree};
+use crate::pcs::ligero::{Ligero, LigeroParameters};
+use crate::pcs::transparent::TransparentPCS;
+use crate::pcs::{HomomorphicPCS, MultilinearPCS};
+use crate::rng::{Blake2b512Rng, FeedableRNG};
+use ark_ff::{Field, One};
+use ark_poly::{DenseMultilinearExtension, MultilinearExtension, Polynomial};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::RngCore;
+use ark_std::vec::Vec;
+use ark_std::{test_rng, UniformRand};
+use ark_test_curves::bls12_381::{Bls12_381, Fr, G1Projective};
+
+/// Sets up the parameters for `num_vars` variables, and commits to `num_polys` random polynomials.
+fn commit_random_polys<F: Field, P: MultilinearPCS<F>>(
+    num_vars: usize,
+    num_polys: usize,
+    rng: &mut impl RngCore,
+) -> (
+    P::Parameters,
+    Vec<DenseMultilinearExtension<F>>,
+    Vec<P::Commitment>,
+) {
+    let pp = P::setup(num_vars, rng).unwrap();
+    let polys: Vec<_> = (0..num_polys)
+        .map(|_| DenseMultilinearExtension::rand(num_vars, rng))
+        .collect();
+    let commitments = polys.iter().map(|p| P::commit(&pp, p).unwrap()).collect();
+    (pp, polys, commitments)
+}
+
+/// Checks that `verify` accepts the claimed `values`, and rejects them once a value is changed.
+fn check_values<F: Field>(mut values: Vec<F>, verify: impl Fn(&[F]) -> Result<bool, crate::Error>) {
+    assert!(verify(&values).unwrap());
+    values[0] += F::one();
+    assert!(!verify(&values).unwrap_or(false));
+}
+
+/// Checks completeness and soundness against a wrong value of a commitment scheme.
+pub(crate) fn test_pcs<F: Field, P: MultilinearPCS<F>>(num_vars: usize) {
+    let mut rng = test_rng();
+    let (pp, polys, commitments) = commit_random_polys::<F, P>(num_vars, 1, &mut rng);
+    let (poly, commitment) = (&polys[0], &commitments[0]);
+    let point: Vec<_> = (0..num_vars).map(|_| F::rand(&mut rng)).collect();
+    let value = poly.evaluate(&point);
+    let proof = P::open(&pp, poly, &point).unwrap();
+    check_values(vec![value], |values| {
+        P::verify(&pp, commitment, &point, values[0], &proof)
+    });
+
+    // commitments and proofs go over the wire
+    let mut bytes = Vec::new();
+    commitment.serialize_compressed(&mut bytes).unwrap();
+    let commitment = P::Commitment::deserialize_compressed(&bytes[..]).unwrap();
+    let mut bytes = Vec::new();
+    proof.serialize_compressed(&mut bytes).unwrap();
+    let proof = P::Proof::deserialize_compressed(&bytes[..]).unwrap();
+    assert!(P::verify(&pp, &commitment, &point, value, &proof).unwrap());
+
+    // the proof does not hold for another polynomial
+    let other = DenseMultilinearExtension::<F>::rand(num_vars, &mut rng);
+    let other_commitment = P::commit(&pp, &other).unwrap();
+    assert!(!P::verify(&pp, &other_commitment, &point, value, &proof).unwrap_or(false));
+}
+
+#[test]
+fn test_transparent() {
+    test_pcs::<Fr, TransparentPCS<Fr>>(5);
+    test_pcs::<Fr, TransparentPCS<Fr>>(0);
+}
+
+#[test]
+fn test_ligero() {
+    for num_vars in [0, 1, 2, 5, 8] {
+        test_pcs::<Fr, Ligero<Fr>>(num_vars);
+    }
+}
+
+#[test]
+fn test_ligero_tampered_proof() {
+    let mut rng = test_rng();
+    let pp = LigeroParameters::new(6, 2, 32);
+    let poly = DenseMultilinearExtension::<Fr>::rand(6, &mut rng);
+    let commitment = Ligero::commit(&pp, &poly).unwrap();
+    let point: Vec<_> = (0..6).map(|_| Fr::rand(&mut rng)).collect();
+    let value = poly.evaluate(&point);
+    let proof = Ligero::open(&pp, &poly, &point).unwrap();
+    assert!(Ligero::verify(&pp, &commitment, &point, value, &proof).unwrap());
+
+    let mut bad = proof.clone();
+    bad.columns[3][1] += Fr::one();
+    assert!(!Ligero::verify(&pp, &commitment, &point, value, &bad).unwrap());
+
+    // a consistent change of the evaluation row still fails the column checks
+    let mut bad = proof.clone();
+    bad.evaluation_row[0] += Fr::one();
+    assert!(!Ligero::verify(&pp, &commitment, &point, value, &bad).unwrap());
+
+    let mut bad = proof;
+    bad.columns.pop();
+    assert!(Ligero::verify(&pp, &commitment, &point, value, &bad).is_err());
+}
+
+/// Checks the sizes of Ligero commitments and openings against the layout of the proof.
+#[test]
+fn test_ligero_sizes() {
+    use crate::pcs::ligero::{DEFAULT_LOG_BLOWUP, DEFAULT_NUM_QUERIES};
+    use crate::pcs::matrix_dimensions;
+
+    let mut rng = test_rng();
+    let field_size = Fr::from(1u64).compressed_size();
+    for nv in [3, 8, 11] {
+        let pp = Ligero::<Fr>::setup(nv, &mut rng).unwrap();
+        let poly = DenseMultilinearExtension::<Fr>::rand(nv, &mut rng);
+        let point: Vec<_> = (0..nv).map(|_| Fr::rand(&mut rng)).collect();
+        let commitment = Ligero::commit(&pp, &poly).unwrap();
+        let proof = Ligero::open(&pp, &poly, &point).unwrap();
+        // lengths are serialized as 8 bytes, and digests as 64 bytes
+        let (row_vars, col_vars) = matrix_dimensions(nv);
+        let row = 8 + field_size * (1 << col_vars);
+        let column = 8 + field_size * (1 << row_vars);
+        let path = 8 + 64 * (col_vars + DEFAULT_LOG_BLOWUP);
+        assert_eq!(commitment.compressed_size(), 8 + 64);
+        assert_eq!(
+            proof.compressed_size(),
+            2 * row + 2 * 8 + DEFAULT_NUM_QUERIES * (column + path)
+        );
+    }
+}
+
+#[test]
+fn test_merkle_tree() {
+    let leaves: Vec<_> = (0u8..8).map(|i| hash_leaf(&[i])).collect();
+    let tree = MerkleTree::new(leaves.clone());
+    let root = tree.root();
+    for (i, leaf) in leaves.iter().enumerate() {
+        let path = tree.path(i);
+        assert!(path.verify(&root, i, leaf));
+        assert!(!path.verify(&root, i ^ 1, leaf));
+        assert!(!path.verify(&root, i + 8, leaf));
+    }
+}
+
+#[test]
+fn test_kzg() {
+    for num_vars in [0, 1, 4, 7] {
+        test_pcs::<Fr, MultilinearKZG<Bls12_381>>(num_vars);
+    }
+}
+
+#[test]
+fn test_kzg_fewer_variables() {
+    // polynomials with fewer variables than the parameters use the last variables of the SRS
+    let mut rng = test_rng();
+    let pp = MultilinearKZG::<Bls12_381>::setup(6, &mut rng).unwrap();
+    let poly = DenseMultilinearExtension::<Fr>::rand(3, &mut rng);
+    let commitment = MultilinearKZG::commit(&pp, &poly).unwrap();
+    let point: Vec<_> = (0..3).map(|_| Fr::rand(&mut rng)).collect();
+    let value = poly.evaluate(&point);
+    let proof = MultilinearKZG::open(&pp, &poly, &point).unwrap();
+    assert!(MultilinearKZG::verify(&pp, &commitment, &point, value, &proof).unwrap());
+
+    let mut bad = proof;
+    bad.quotients.swap(0, 1);
+    assert!(!MultilinearKZG::verify(&pp, &commitment, &point, value, &bad).unwrap());
+
+    let big = DenseMultilinearExtension::<Fr>::rand(7, &mut rng);
+    assert!(MultilinearKZG::commit(&pp, &big).is_err());
+}
+
+#[test]
+fn test_hyrax() {
+    for num_vars in [0, 1, 2, 5, 8] {
+        test_pcs::<Fr, Hyrax<G1Projective>>(num_vars);
+    }
+}
+
+#[test]
+fn test_hyrax_tampered_proof() {
+    let mut rng = test_rng();
+    let pp = Hyrax::<G1Projective>::setup(6, &mut rng).unwrap();
+    let poly = DenseMultilinearExtension::<Fr>::rand(5, &mut rng);
+    let commitment = Hyrax::commit(&pp, &poly).unwrap();
+    let point: Vec<_> = (0..5).map(|_| Fr::rand(&mut rng)).collect();
+    let value = poly.evaluate(&point);
+    let proof = Hyrax::open(&pp, &poly, &point).unwrap();
+    assert!(Hyrax::verify(&pp, &commitment, &point, value, &proof).unwrap());
+    assert_eq!(
+        Hyrax::open_with_commitment(&pp, &poly, &commitment, &point).unwrap(),
+        proof
+    );
+
+    let mut bad = proof.clone();
+    bad.a += Fr::one();
+    assert!(!Hyrax::verify(&pp, &commitment, &point, value, &bad).unwrap());
+
+    let mut bad = proof.clone();
+    bad.left.swap(0, 1);
+    assert!(!Hyrax::verify(&pp, &commitment, &point, value, &bad).unwrap());
+
+    let mut bad = proof;
+    bad.right.pop();
+    assert!(Hyrax::verify(&pp, &commitment, &point, value, &bad).is_err());
+}
+
+fn test_batch<P: HomomorphicPCS<Fr>>(num_vars: usize, num_polys: usize) {
+    let mut rng = test_rng();
+    let (pp, polys, commitments) = commit_random_polys::<_, P>(num_vars, num_polys, &mut rng);
+    let point: Vec<_> = (0..num_vars).map(|_| Fr::rand(&mut rng)).collect();
+    let values: Vec<_> = polys.iter().map(|p| p.evaluate(&point)).collect();
+
+    let mut fs_rng = Blake2b512Rng::setup();
+    let proof = batch_open::<_, P>(&mut fs_rng, &pp, &polys, &commitments, &point).unwrap();
+    check_values(values, |values| {
+        let mut fs_rng = Blake2b512Rng::setup();
+        batch_verify::<_, P>(&mut fs_rng, &pp, &commitments, &point, values, &proof)
+    });
+
+    // combinations need exactly one coefficient per commitment
+    let coefficients = vec![Fr::one(); num_polys + 1];
+    for coefficients in [&coefficients[..], &coefficients[..num_polys - 1]] {
+        assert!(matches!(
+            P::combine_commitments(&commitments, coefficients),
+            Err(crate::Error::Reject(_))
+        ));
+    }
+}
+
+fn test_multi_point<P: HomomorphicPCS<Fr>>(num_vars: usize, num_polys: usize) {
+    let mut rng = test_rng();
+    let (pp, polys, commitments) = commit_random_polys::<_, P>(num_vars, num_polys, &mut rng);
+    let points: Vec<Vec<_>> = (0..num_polys)
+        .map(|_| (0..num_vars).map(|_| Fr::rand(&mut rng)).collect())
+        .collect();
+    let values: Vec<_> = polys
+        .iter()
+        .zip(&points)
+        .map(|(p, z)| p.evaluate(z))
+        .collect();
+
+    let mut fs_rng = Blake2b512Rng::setup();
+    let proof = multi_point_open::<_, P>(&mut fs_rng, &pp, &polys, &commitments, &points).unwrap();
+    check_values(values, |values| {
+        let mut fs_rng = Blake2b512Rng::setup();
+        multi_point_verify::<_, P>(&mut fs_rng, &pp, &commitments, &points, values, &proof)
+    });
+}
+
+#[test]
+fn test_batch_opening() {
+    for (num_vars, num_polys) in [(0, 2), (1, 1), (4, 3), (7, 5)] {
+        test_batch::<TransparentPCS<Fr>>(num_vars, num_polys);
+        test_batch::<MultilinearKZG<Bls12_381>>(num_vars, num_polys);
+        test_batch::<Hyrax<G1Projective>>(num_vars, num_polys);
+    }
+}
+
+#[test]
+fn test_multi_point_opening() {
+    for (num_vars, num_polys) in [(0, 2), (1, 1), (4, 3), (7, 5)] {
+        test_multi_point::<TransparentPCS<Fr>>(num_vars, num_polys);
+        test_multi_point::<MultilinearKZG<Bls12_381>>(num_vars, num_polys);
+        test_multi_point::<Hyrax<G1Projective>>(num_vars, num_polys);
+    }
+}
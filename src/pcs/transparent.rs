@@ -0,0 +1,83 @@
+//! Transparent "commitment" that reveals the polynomial, for tests and debugging.
+
+use crate::pcs::{common_num_vars, HomomorphicPCS, MultilinearPCS};
+use ark_ff::Field;
+use ark_poly::{DenseMultilinearExtension, Polynomial};
+use ark_std::marker::PhantomData;
+use ark_std::rand::RngCore;
+
+/// Commitment scheme whose commitment is the polynomial itself and whose proofs are empty.
+///
+/// It is neither hiding nor succinct, and should only be used to test protocols that are generic
+/// over a `MultilinearPCS`.
+pub struct TransparentPCS<F: Field> {
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> MultilinearPCS<F> for TransparentPCS<F> {
+    /// maximum number of variables
+    type Parameters = usize;
+    type Commitment = DenseMultilinearExtension<F>;
+    type Proof = ();
+
+    fn setup<R: RngCore>(num_vars: usize, _rng: &mut R) -> Result<Self::Parameters, crate::Error> {
+        Ok(num_vars)
+    }
+
+    fn commit(
+        pp: &Self::Parameters,
+        poly: &DenseMultilinearExtension<F>,
+    ) -> Result<Self::Commitment, crate::Error> {
+        if poly.num_vars > *pp {
+            return Err(crate::Error::OtherError(
+                "Polynomial has too many variables for the parameters.".into(),
+            ));
+        }
+        Ok(poly.clone())
+    }
+
+    fn open(
+        _pp: &Self::Parameters,
+        _poly: &DenseMultilinearExtension<F>,
+        _point: &[F],
+    ) -> Result<Self::Proof, crate::Error> {
+        Ok(())
+    }
+
+    fn verify(
+        _pp: &Self::Parameters,
+        commitment: &Self::Commitment,
+        point: &[F],
+        value: F,
+        _proof: &Self::Proof,
+    ) -> Result<bool, crate::Error> {
+        if commitment.num_vars != point.len() {
+            return Err(crate::Error::Reject(Some(
+                "Point has the wrong number of variables.".into(),
+            )));
+        }
+        Ok(commitment.evaluate(&point.to_vec()) == value)
+    }
+}
+
+impl<F: Field> HomomorphicPCS<F> for TransparentPCS<F> {
+    fn combine_commitments(
+        commitments: &[Self::Commitment],
+        coefficients: &[F],
+    ) -> Result<Self::Commitment, crate::Error> {
+        let num_vars = common_num_vars(commitments, coefficients, |c| c.num_vars)?;
+        let evaluations = (0..1 << num_vars)
+            .map(|b| {
+                commitments
+                    .iter()
+                    .zip(coefficients)
+                    .map(|(c, coefficient)| *coefficient * c.evaluations[b])
+                    .sum()
+            })
+            .collect();
+        Ok(DenseMultilinearExtension::from_evaluations_vec(
+            num_vars,
+            evaluations,
+        ))
+    }
+}
@@ -12,9 +12,10 @@ use ark_poly::DenseMultilinearExtension;
 use ark_std::rand::Rng;
 use fj_poly::build_fj_polynomial;
 use q_poly::build_Q_polynomial;
-use std::{rc::Rc, sync::Arc};
+use std::sync::Arc;
 
 use crate::ml_sumcheck::{protocol::ListOfProductsOfPolynomials, MLSumcheck, Proof};
+use crate::pcs::MultilinearPCS;
 
 /// SumFoldInstance contains the information needed to construct a SumFoldProof.
 /// It contains a function F_func that takes a slice of Scalars (e.g. g0(x), g1(x), ...) and returns a single Scalar.
@@ -119,7 +120,7 @@ impl<F: Field> SumFoldProof<F> {
         let dim = g_vec[0].num_vars;
         let mut poly: ListOfProductsOfPolynomials<F> = ListOfProductsOfPolynomials::new(dim);
         poly.add_product(
-            g_vec.into_iter().map(|poly| Rc::new(poly.clone())),
+            g_vec.into_iter().map(|poly| Arc::new(poly.clone())),
             F::one(),
         );
         poly
@@ -141,12 +142,21 @@ impl<F: Field> SumFoldProof<F> {
         MLSumcheck::verify(&poly.info(), claim, &proof).is_ok()
     }
 
+    /// Commits to the fj polynomials with a multilinear polynomial commitment scheme, so that the
+    /// claims on the folded instance can be discharged by opening the commitments.
+    pub fn commit_fj_polys<P: MultilinearPCS<F>>(
+        &self,
+        pp: &P::Parameters,
+    ) -> Result<Vec<P::Commitment>, crate::Error> {
+        self.fj_poly.iter().map(|fj| P::commit(pp, fj)).collect()
+    }
+
     /// Verifies the SumFoldProof by following the requested steps:
     // 1. pick random index rho
     // 2. evaluate Ti from fj polys
     // 3. check if Ti = claim by sumcheck protocol
     // 4. apply sumcheck protocol
-    // 5. commit to fj polys
+    // 5. commit to fj polys, see `commit_fj_polys`
     // 6. return Ti, commit(f1), commit(f2), ..., commit(ft)
     #[allow(non_snake_case)]
     pub fn verify<R: Rng>(&self, rng: &mut R) -> Result<bool, Error> {
@@ -1,7 +1,11 @@
 use super::*;
+use crate::pcs::hyrax::Hyrax;
+use crate::pcs::kzg::MultilinearKZG;
+use crate::pcs::transparent::TransparentPCS;
 use crate::sumfold::utils::{build_random_poly, product_f};
+use ark_poly::Polynomial;
 use ark_std::{rand::rngs::StdRng, rand::SeedableRng, One};
-use ark_test_curves::bls12_381::Fr as FF;
+use ark_test_curves::bls12_381::{Bls12_381, Fr as FF, G1Projective};
 
 #[test]
 fn test_sumfold_correctness() {
@@ -114,3 +118,69 @@ fn test_sumfold_soundness() {
         }
     }
 }
+
+/// Folds 4 random instances of the product of 2 polynomials in 3 variables.
+fn build_random_sumfold_proof<R: Rng>(rng: &mut R) -> SumFoldProof<FF> {
+    let instances: Vec<_> = (0..4)
+        .map(|_| SumFoldInstance {
+            F_func: Arc::new(product_f),
+            g_vec: vec![build_random_poly(3, rng), build_random_poly(3, rng)],
+        })
+        .collect();
+    SumFoldProof::sumfold(instances, rng)
+}
+
+fn test_commit_fj_polys<P: MultilinearPCS<FF>>() {
+    let mut rng = StdRng::seed_from_u64(99);
+    let proof = build_random_sumfold_proof(&mut rng);
+
+    let num_vars = proof.fj_poly[0].num_vars;
+    let pp = P::setup(num_vars, &mut rng).unwrap();
+    let commitments = proof.commit_fj_polys::<P>(&pp).unwrap();
+    assert_eq!(commitments.len(), proof.fj_poly.len());
+    let point: Vec<_> = (0..num_vars).map(|i| FF::from(i as u64)).collect();
+    for (fj, commitment) in proof.fj_poly.iter().zip(&commitments) {
+        let opening = P::open(&pp, fj, &point).unwrap();
+        let value = fj.evaluate(&point);
+        assert!(P::verify(&pp, commitment, &point, value, &opening).unwrap());
+    }
+}
+
+#[test]
+fn test_commit_fj_polys_transparent() {
+    test_commit_fj_polys::<TransparentPCS<FF>>();
+}
+
+#[test]
+fn test_commit_fj_polys_kzg() {
+    test_commit_fj_polys::<MultilinearKZG<Bls12_381>>();
+}
+
+#[test]
+fn test_commit_fj_polys_hyrax() {
+    test_commit_fj_polys::<Hyrax<G1Projective>>();
+}
+
+#[test]
+fn test_batch_open_fj_polys() {
+    use crate::pcs::batch::{batch_open, batch_verify};
+    use crate::rng::{Blake2b512Rng, FeedableRNG};
+    type Kzg = MultilinearKZG<Bls12_381>;
+
+    let mut rng = StdRng::seed_from_u64(99);
+    let proof = build_random_sumfold_proof(&mut rng);
+
+    // all the fj polys are opened at the same point with a single opening
+    let num_vars = proof.fj_poly[0].num_vars;
+    let pp = Kzg::setup(num_vars, &mut rng).unwrap();
+    let commitments = proof.commit_fj_polys::<Kzg>(&pp).unwrap();
+    let point: Vec<_> = (0..num_vars).map(|i| FF::from(i as u64 + 3)).collect();
+    let values: Vec<_> = proof.fj_poly.iter().map(|fj| fj.evaluate(&point)).collect();
+    let mut fs_rng = Blake2b512Rng::setup();
+    let opening =
+        batch_open::<_, Kzg>(&mut fs_rng, &pp, &proof.fj_poly, &commitments, &point).unwrap();
+    let mut fs_rng = Blake2b512Rng::setup();
+    assert!(
+        batch_verify::<_, Kzg>(&mut fs_rng, &pp, &commitments, &point, &values, &opening).unwrap()
+    );
+}
@@ -0,0 +1,59 @@
+//! Utility functions for multilinear polynomials shared by the protocols of this crate
+use ark_ff::Field;
+use ark_std::vec::Vec;
+
+/// Returns the evaluations of `eq(point, ·)` over the boolean hypercube, i.e. the table `T` of
+/// length `2^point.len()` such that `T[b] = eq(point, b)`.
+///
+/// The index `b` is in little endian form, matching the convention of `DenseMultilinearExtension`:
+/// the i-th bit of `b` corresponds to `point[i]`.
+pub fn eq_evaluations<F: Field>(point: &[F]) -> Vec<F> {
+    let mut table = vec![F::zero(); 1 << point.len()];
+    table[0] = F::one();
+    for (i, r) in point.iter().enumerate() {
+        for b in 0..(1 << i) {
+            let prev = table[b];
+            table[b + (1 << i)] = prev * r;
+            table[b] = prev - table[b + (1 << i)];
+        }
+    }
+    table
+}
+
+/// Evaluates `eq(x, y) = prod_i (x_i * y_i + (1 - x_i) * (1 - y_i))`.
+pub fn eq_eval<F: Field>(x: &[F], y: &[F]) -> F {
+    assert_eq!(x.len(), y.len());
+    x.iter()
+        .zip(y)
+        .map(|(a, b)| *a * b + (F::one() - a) * (F::one() - b))
+        .product()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{eq_eval, eq_evaluations};
+    use ark_poly::{DenseMultilinearExtension, MultilinearExtension, Polynomial};
+    use ark_std::vec::Vec;
+    use ark_std::{test_rng, UniformRand};
+    use ark_test_curves::bls12_381::Fr;
+
+    #[test]
+    fn test_eq_evaluations() {
+        let mut rng = test_rng();
+        let nv = 6;
+        let point: Vec<_> = (0..nv).map(|_| Fr::rand(&mut rng)).collect();
+        let poly = DenseMultilinearExtension::<Fr>::rand(nv, &mut rng);
+        // <eq(point, ·), poly> over the hypercube is poly(point)
+        let expected = poly.evaluate(&point);
+        let actual: Fr = eq_evaluations(&point)
+            .iter()
+            .zip(poly.evaluations.iter())
+            .map(|(e, p)| *e * p)
+            .sum();
+        assert_eq!(actual, expected);
+
+        let other: Vec<_> = (0..nv).map(|_| Fr::rand(&mut rng)).collect();
+        let eq_point = DenseMultilinearExtension::from_evaluations_vec(nv, eq_evaluations(&point));
+        assert_eq!(eq_point.evaluate(&other), eq_eval(&point, &other));
+    }
+}
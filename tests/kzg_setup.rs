@@ -0,0 +1,19 @@
+//! Checks that the multilinear KZG does not generate parameters with a known trapdoor outside of
+//! the unit tests of the crate.
+//!
+//! This is an integration test so that the library is built without `cfg(test)`.
+
+use ark_std::test_rng;
+use ark_test_curves::bls12_381::Bls12_381;
+use gkrfold::pcs::kzg::{MultilinearKZG, MultilinearKZGParameters};
+use gkrfold::pcs::MultilinearPCS;
+
+#[test]
+fn test_kzg_setup_needs_trusted_setup() {
+    let mut rng = test_rng();
+    assert!(MultilinearKZG::<Bls12_381>::setup(4, &mut rng).is_err());
+
+    // parameters generated explicitly for testing still work
+    let pp = MultilinearKZGParameters::<Bls12_381>::setup_for_testing(4, &mut rng);
+    assert_eq!(pp.num_vars, 4);
+}
@@ -0,0 +1,78 @@
+//! Measures the peak memory of the sumcheck prover with a counting allocator.
+//!
+//! This is an integration test so that the global allocator only sees this test.
+
+use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+use ark_std::sync::Arc;
+use ark_std::test_rng;
+use ark_test_curves::bls12_381::Fr;
+use gkrfold::ml_sumcheck::data_structures::ListOfProductsOfPolynomials;
+use gkrfold::ml_sumcheck::MLSumcheck;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct CountingAllocator;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            PEAK.fetch_max(current, Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[test]
+fn test_prover_peak_memory() {
+    let nv = 20;
+    let mut rng = test_rng();
+    let multiplicands: Vec<_> = (0..3)
+        .map(|_| Arc::new(DenseMultilinearExtension::<Fr>::rand(nv, &mut rng)))
+        .collect();
+    let mut poly = ListOfProductsOfPolynomials::new(nv);
+    poly.add_product(
+        vec![multiplicands[0].clone(), multiplicands[1].clone()],
+        Fr::from(1u64),
+    );
+    poly.add_product(
+        vec![multiplicands[1].clone(), multiplicands[2].clone()],
+        Fr::from(2u64),
+    );
+    let witness_size = 3 * (1 << nv) * std::mem::size_of::<Fr>();
+
+    let baseline = CURRENT.load(Ordering::SeqCst);
+    PEAK.store(baseline, Ordering::SeqCst);
+    let proof = MLSumcheck::prove(&poly).unwrap();
+    let overhead = PEAK.load(Ordering::SeqCst) - baseline;
+
+    // the tables of the first round are shared, and fixing the first variable allocates tables of
+    // half the size, so the prover needs about half of the witness on top of it
+    assert!(
+        overhead >= witness_size / 2,
+        "overhead of {} bytes is below the halved tables",
+        overhead
+    );
+    assert!(
+        overhead < witness_size * 6 / 10,
+        "overhead of {} bytes for a witness of {} bytes",
+        overhead,
+        witness_size
+    );
+
+    let subclaim =
+        MLSumcheck::verify(&poly.info(), MLSumcheck::extract_sum(&proof), &proof).unwrap();
+    assert_eq!(poly.evaluate(&subclaim.point), subclaim.expected_evaluation);
+}